@@ -0,0 +1,91 @@
+use crate::case_file::CaseFile;
+use crate::content::Content;
+
+/// An authored case in the campaign. Chapters are played in order and get
+/// harder as they go, and some of the people in them turn up more than once.
+pub struct Chapter {
+    pub title: &'static str,
+    source: &'static str,
+}
+
+impl Chapter {
+    pub fn load(&self, content: &Content) -> Result<CaseFile, String> {
+        CaseFile::from_ron(self.source, content)
+    }
+}
+
+pub const CHAPTERS: [Chapter; 3] = [
+    Chapter {
+        title: "The Doyle Affair",
+        source: include_str!("../cases/campaign/01-the-doyle-affair.ron"),
+    },
+    Chapter {
+        title: "Fontaine's Last Night",
+        source: include_str!("../cases/campaign/02-fontaines-last-night.ron"),
+    },
+    Chapter {
+        title: "Two Sets of Hands",
+        source: include_str!("../cases/campaign/03-two-sets-of-hands.ron"),
+    },
+];
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum ChapterStatus {
+    Solved,
+    Open,
+    Locked,
+}
+
+/// How far the player has got through the campaign, and which chapter, if any,
+/// is being played right now.
+pub struct Campaign {
+    pub solved: usize,
+    pub current: Option<usize>,
+}
+
+impl Campaign {
+    pub fn new(solved: usize) -> Self {
+        Campaign {
+            solved: solved.min(CHAPTERS.len()),
+            current: None,
+        }
+    }
+
+    pub fn status(&self, chapter: usize) -> ChapterStatus {
+        if chapter < self.solved {
+            ChapterStatus::Solved
+        } else if chapter == self.solved {
+            ChapterStatus::Open
+        } else {
+            ChapterStatus::Locked
+        }
+    }
+
+    /// The first chapter that hasn't been solved yet, or the last one once the
+    /// whole campaign is done.
+    pub fn next_chapter(&self) -> usize {
+        self.solved.min(CHAPTERS.len() - 1)
+    }
+
+    /// Marks the chapter being played as solved, unlocking the one after it.
+    pub fn solve_current(&mut self) {
+        if let Some(chapter) = self.current {
+            self.solved = self.solved.max(chapter + 1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_chapter_loads() {
+        let content = Content::load();
+        for chapter in CHAPTERS.iter() {
+            if let Err(e) = chapter.load(&content) {
+                panic!("{}: {}", chapter.title, e);
+            }
+        }
+    }
+}
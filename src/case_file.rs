@@ -0,0 +1,215 @@
+use serde::{Deserialize, Serialize};
+
+use crate::config::Difficulty;
+use crate::content::Content;
+use crate::story::{Note, Story};
+use crate::verifier::verify;
+
+/// Bumped whenever older case files can no longer be read. Fields added since
+/// are given defaults instead, so files written before them still load.
+pub const CASE_FILE_VERSION: u32 = 1;
+
+/// A whole case, solution included, as it is written to and read from disk.
+#[derive(Serialize, Deserialize)]
+pub struct CaseFile {
+    pub version: u32,
+    pub difficulty: Difficulty,
+    pub story: Story,
+}
+
+impl CaseFile {
+    pub fn new(story: &Story, difficulty: Difficulty) -> Self {
+        CaseFile {
+            version: CASE_FILE_VERSION,
+            difficulty,
+            story: story.clone(),
+        }
+    }
+
+    pub fn to_ron(&self) -> String {
+        match ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::new()) {
+            Ok(ron) => ron,
+            Err(e) => panic!("Failed to write case #{}: {}", self.story.seed, e),
+        }
+    }
+
+    /// Reads a case back in, rejecting it if it was written by a different
+    /// version, names things the content doesn't have, or has been edited into
+    /// something unsolvable.
+    pub fn from_ron(ron: &str, content: &Content) -> Result<Self, String> {
+        let case: CaseFile = ron::de::from_str(ron).map_err(|e| e.to_string())?;
+
+        if case.version != CASE_FILE_VERSION {
+            return Err(format!(
+                "case file version {} is not supported (expected {})",
+                case.version, CASE_FILE_VERSION
+            ));
+        }
+
+        check_names(&case.story, content)?;
+        verify(&case.story)?;
+
+        Note::reserve_ids(case.max_note_id());
+
+        Ok(case)
+    }
+
+    fn max_note_id(&self) -> u32 {
+        let story = &self.story;
+
+        let markers = story
+            .clues
+            .iter()
+            .chain(Some(&story.victim.clue))
+            .chain(story.strikes.iter().map(|strike| &strike.clue))
+            .flat_map(|clue| clue.markers.iter().map(|marker| marker.2.id));
+        let report = story.victim.report.iter().map(|note| note.id);
+        let convos = story
+            .suspects
+            .iter()
+            .flat_map(|s| s.convo_options.iter())
+            .chain(story.witnesses.iter().flat_map(|w| w.convo_options.iter()))
+            .filter_map(|(_, _, note)| note.as_ref().map(|note| note.id));
+        let deductions = story.connections.iter().map(|cxn| cxn.note.id);
+
+        markers
+            .chain(report)
+            .chain(convos)
+            .chain(deductions)
+            .max()
+            .unwrap_or(0)
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn save(&self, dir: &str) -> Result<String, String> {
+        let path = format!("{}/case-{}.ron", dir, self.story.seed);
+
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        std::fs::write(&path, self.to_ron()).map_err(|e| e.to_string())?;
+
+        Ok(path)
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load(path: &str, content: &Content) -> Result<Self, String> {
+        let ron = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        CaseFile::from_ron(&ron, content)
+    }
+}
+
+/// Everything a case refers to by name has to exist in the content, since the
+/// game looks those names up while the case is being played.
+fn check_names(story: &Story, content: &Content) -> Result<(), String> {
+    fn known(what: &str, name: &str, names: &[String]) -> Result<(), String> {
+        if names.iter().any(|n| n == name) {
+            Ok(())
+        } else {
+            Err(format!("unknown {} \"{}\"", what, name))
+        }
+    }
+
+    let victim = &story.victim;
+
+    let weapons: Vec<String> = content.weapons.iter().map(|w| w.name.to_string()).collect();
+    known("weapon", &victim.weapon_used, &weapons)?;
+    for clue in story.clues.iter() {
+        known("weapon", &clue.name, &weapons)?;
+    }
+
+    let traits: Vec<String> = content
+        .evidence_traits
+        .iter()
+        .map(|t| t.name.to_string())
+        .collect();
+    for (attribute, _) in victim.evidence.iter() {
+        known("trait", attribute, &traits)?;
+    }
+
+    let relationships: Vec<String> = content
+        .relationships
+        .iter()
+        .map(|r| r.name.to_string())
+        .collect();
+    known("relationship", &victim.relationship_found, &relationships)?;
+
+    let buildings: Vec<String> = content
+        .buildings
+        .iter()
+        .map(|b| b.name.to_string())
+        .collect();
+    known("building", &story.building, &buildings)?;
+    let rooms = &content.building(&story.building).rooms;
+
+    known("room", &victim.crime_scene, rooms)?;
+    for strike in story.strikes.iter() {
+        known("room", &strike.crime_scene, rooms)?;
+    }
+    if let Some(lock) = &story.lock {
+        known("room", &lock.room, rooms)?;
+    }
+    for suspect in story.suspects.iter() {
+        known("relationship", &suspect.relationship, &relationships)?;
+        for (_, location) in suspect.whereabouts.iter() {
+            known("room", location, rooms)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::CaseConfig;
+    use rltk::RandomNumberGenerator;
+
+    fn case_at(content: &Content, difficulty: Difficulty) -> CaseFile {
+        let mut rng = RandomNumberGenerator::seeded(7);
+        let story = Story::gen_rand(7, &mut rng, content, &CaseConfig::new(difficulty));
+        CaseFile::new(&story, difficulty)
+    }
+
+    fn case(content: &Content) -> CaseFile {
+        case_at(content, Difficulty::Hard)
+    }
+
+    #[test]
+    fn cases_survive_a_round_trip() {
+        let content = Content::load();
+        for difficulty in [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard] {
+            let case = case_at(&content, difficulty);
+            let loaded = CaseFile::from_ron(&case.to_ron(), &content).unwrap();
+
+            assert_eq!(loaded.difficulty, difficulty);
+            assert_eq!(format!("{:?}", loaded.story), format!("{:?}", case.story));
+            assert_eq!(loaded.to_ron(), case.to_ron());
+        }
+    }
+
+    #[test]
+    fn unknown_rooms_are_rejected() {
+        let content = Content::load();
+        let mut case = case(&content);
+        case.story.victim.crime_scene = "the attic".to_string();
+
+        assert_eq!(
+            CaseFile::from_ron(&case.to_ron(), &content).err(),
+            Some("unknown room \"the attic\"".to_string())
+        );
+    }
+
+    #[test]
+    fn missing_whereabouts_are_rejected() {
+        let content = Content::load();
+        let mut case = case(&content);
+        case.story.suspects[0].whereabouts.clear();
+
+        assert_eq!(
+            CaseFile::from_ron(&case.to_ron(), &content).err(),
+            Some(format!(
+                "{} has no whereabouts",
+                case.story.suspects[0].name
+            ))
+        );
+    }
+}
@@ -1,17 +1,21 @@
-use rltk::RGB;
+use std::collections::HashSet;
+
+use rltk::{RandomNumberGenerator, RGB};
 use specs::prelude::*;
 use specs_derive::Component;
 
 use crate::{
-    gui::Options,
+    gui::{Log, Options, Time},
     map::{Map, Tile},
-    story::{Clue, Suspect},
+    story::{Clue, CoronerReport, PlayerNotes, SerialKiller, Story, Suspect},
+    RunState,
 };
 
 #[derive(Component)]
 pub struct Position {
     pub x: i32,
     pub y: i32,
+    pub level: i32,
 }
 
 #[derive(Component)]
@@ -25,6 +29,7 @@ pub struct Renderable {
 pub struct PlayerPosition {
     pub x: i32,
     pub y: i32,
+    pub level: i32,
 }
 
 #[derive(Component, Clone, Copy, Default)]
@@ -34,33 +39,74 @@ pub struct MovementAI {
     pub max_cooldown: i32,
 }
 
+/// Keeps the detective's view of the building in step with where they stand
+/// and which doors are open.
+pub struct VisibilityChecker {}
+
+impl<'a> System<'a> for VisibilityChecker {
+    type SystemData = (
+        ReadExpect<'a, PlayerPosition>,
+        ReadExpect<'a, Time>,
+        WriteExpect<'a, Map>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (player_pos, time, mut map) = data;
+
+        map.update_view(
+            player_pos.x,
+            player_pos.y,
+            player_pos.level,
+            time.daylight(),
+        );
+    }
+}
+
 pub struct MovementChecker {}
 
 impl<'a> System<'a> for MovementChecker {
     type SystemData = (
         WriteStorage<'a, Position>,
         WriteStorage<'a, MovementAI>,
-        ReadExpect<'a, Map>,
+        WriteExpect<'a, Map>,
+        ReadExpect<'a, Time>,
+        WriteExpect<'a, RandomNumberGenerator>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
-        let (mut positions, mut ais, map) = data;
+        let (mut positions, mut ais, mut map, time, mut rng) = data;
+
+        // At night people keep to their rooms and move about less. By day they
+        // wander between floors, going wherever the stairs they step on lead.
+        let night = time.is_night();
 
         for (pos, ai) in (&mut positions, &mut ais).join() {
             if !ai.is_idle {
                 if ai.cooldown > 0 {
                     ai.cooldown -= 1;
                 } else {
-                    ai.cooldown = ai.max_cooldown;
-
-                    let mut rng = rltk::RandomNumberGenerator::new();
+                    ai.cooldown = if night {
+                        ai.max_cooldown * 2
+                    } else {
+                        ai.max_cooldown
+                    };
 
                     let dx = rng.range(-1, 2);
                     let dy = rng.range(-1, 2);
 
-                    if map.get_tile(pos.x + dx, pos.y + dy) == Tile::Floor {
-                        pos.x = pos.x + dx;
-                        pos.y = pos.y + dy;
+                    let (x, y) = (pos.x + dx, pos.y + dy);
+
+                    match map.get_tile(x, y, pos.level) {
+                        Tile::ClosedDoor if !night => map.set_tile(x, y, pos.level, Tile::OpenDoor),
+                        Tile::UpStairs | Tile::DownStairs if night => {}
+                        tile if tile.is_walkable() => {
+                            let (x, y, level) =
+                                map.stairs_to(x, y, pos.level).unwrap_or((x, y, pos.level));
+                            pos.x = x;
+                            pos.y = y;
+                            pos.level = level;
+                        }
+                        _ => {}
                     }
                 }
             }
@@ -69,11 +115,7 @@ impl<'a> System<'a> for MovementChecker {
 }
 
 #[derive(Component, Clone, Copy, Default)]
-pub struct ConversationAI {
-    pub innocent: bool,
-    pub evidence_hair: bool,
-    pub evidence_shoe_size: bool,
-}
+pub struct ConversationAI {}
 
 #[derive(Component, Clone, Copy)]
 pub struct TalkEntity {
@@ -85,7 +127,6 @@ pub struct ConversationChecker {}
 impl<'a> System<'a> for ConversationChecker {
     type SystemData = (
         Entities<'a>,
-        ReadStorage<'a, Suspect>,
         ReadStorage<'a, ConversationAI>,
         WriteStorage<'a, MovementAI>,
         ReadStorage<'a, Position>,
@@ -95,32 +136,21 @@ impl<'a> System<'a> for ConversationChecker {
     );
 
     fn run(&mut self, data: Self::SystemData) {
-        let (
-            entities,
-            suspects,
-            conversables,
-            mut move_ai,
-            positions,
-            player_pos,
-            mut options,
-            mut talk,
-        ) = data;
+        let (entities, conversables, mut move_ai, positions, player_pos, mut options, mut talk) =
+            data;
 
         options.remove_option('T');
 
-        for (ent, _suspect, pos, _conversation, ai) in (
-            &entities,
-            &suspects,
-            &positions,
-            &conversables,
-            &mut move_ai,
-        )
-            .join()
+        for (ent, pos, _conversation, ai) in
+            (&entities, &positions, &conversables, &mut move_ai).join()
         {
-            if (pos.x - player_pos.x).abs() <= 1 && (pos.y - player_pos.y).abs() <= 1 {
+            if pos.level == player_pos.level
+                && (pos.x - player_pos.x).abs() <= 1
+                && (pos.y - player_pos.y).abs() <= 1
+            {
                 options.add_option('T', "Talk");
                 ai.is_idle = true;
-                (*talk).entity = ent;
+                talk.entity = ent;
             } else {
                 ai.is_idle = false;
             }
@@ -151,10 +181,169 @@ impl<'a> System<'a> for ExaminationChecker {
         options.remove_option('X');
 
         for (ent, _clue, pos) in (&entities, &clues, &positions).join() {
-            if (pos.x - player_pos.x).abs() <= 1 && (pos.y - player_pos.y).abs() <= 1 {
+            if pos.level == player_pos.level
+                && (pos.x - player_pos.x).abs() <= 1
+                && (pos.y - player_pos.y).abs() <= 1
+            {
                 options.add_option('X', "Examine");
-                (*exam).entity = ent;
+                exam.entity = ent;
             }
         }
     }
 }
+
+pub struct CoronerChecker {}
+
+impl<'a> System<'a> for CoronerChecker {
+    type SystemData = (
+        ReadExpect<'a, Time>,
+        WriteExpect<'a, CoronerReport>,
+        WriteExpect<'a, Log>,
+        WriteExpect<'a, PlayerNotes>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (time, mut report, mut log, mut notes) = data;
+
+        if report.delivered || time.total_minutes() < report.due {
+            return;
+        }
+
+        log.log_message(
+            &time,
+            "Coroner",
+            &format!("The report on {} just came in.", report.victim),
+            RGB::named(rltk::LIGHTBLUE),
+        );
+
+        for note in report.notes.iter() {
+            log.log_message(
+                &time,
+                "Coroner",
+                note.get_log_msg().as_str(),
+                RGB::named(rltk::LIGHTBLUE),
+            );
+            notes.add_note(note.clone());
+        }
+
+        report.delivered = true;
+    }
+}
+
+/// Unlocks the locked room as soon as the detective has its key.
+pub struct KeyChecker {}
+
+impl<'a> System<'a> for KeyChecker {
+    type SystemData = (
+        ReadExpect<'a, Story>,
+        ReadExpect<'a, Time>,
+        ReadExpect<'a, PlayerNotes>,
+        WriteExpect<'a, Map>,
+        WriteExpect<'a, Log>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (story, time, notes, mut map, mut log) = data;
+
+        let lock = match &story.lock {
+            Some(lock) => lock,
+            None => return,
+        };
+
+        if notes.notes.iter().any(|note| note.id == lock.key) && map.unlock(&lock.room) {
+            log.log_message(
+                &time,
+                "Game",
+                &format!("You can now get into {}.", lock.room),
+                RGB::named(rltk::WHITE),
+            );
+        }
+    }
+}
+
+pub struct SerialChecker {}
+
+impl<'a> System<'a> for SerialChecker {
+    type SystemData = (
+        Entities<'a>,
+        ReadExpect<'a, RunState>,
+        WriteExpect<'a, Time>,
+        WriteExpect<'a, SerialKiller>,
+        WriteExpect<'a, Log>,
+        ReadExpect<'a, Map>,
+        ReadExpect<'a, PlayerPosition>,
+        WriteExpect<'a, RandomNumberGenerator>,
+        ReadStorage<'a, Suspect>,
+        WriteStorage<'a, Position>,
+        WriteStorage<'a, Renderable>,
+        WriteStorage<'a, Clue>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            runstate,
+            mut time,
+            mut killer,
+            mut log,
+            map,
+            player_pos,
+            mut rng,
+            suspects,
+            mut positions,
+            mut renderables,
+            mut clues,
+        ) = data;
+
+        // Nobody gets killed off mid-conversation.
+        if *runstate != RunState::AwaitingInput
+            || killer.struck >= killer.strikes.len()
+            || time.total_minutes() < killer.due
+        {
+            return;
+        }
+
+        let strike = killer.strikes[killer.struck].clone();
+
+        time.next_morning();
+        log.log_message(
+            &time,
+            "Chief",
+            &killer.news[killer.struck],
+            RGB::named(rltk::LIGHTBLUE),
+        );
+
+        if strike.was_suspect {
+            for (ent, suspect) in (&entities, &suspects).join() {
+                if suspect.name == strike.victim {
+                    entities.delete(ent).expect("Unable to delete suspect");
+                }
+            }
+        }
+
+        killer.struck += 1;
+        killer.due = time.total_minutes() + killer.delay;
+
+        // The body turns up somewhere nobody, and nothing, is already.
+        let mut occupied: HashSet<(i32, i32, i32)> = (&positions)
+            .join()
+            .map(|pos| (pos.x, pos.y, pos.level))
+            .collect();
+        occupied.insert((player_pos.x, player_pos.y, player_pos.level));
+        let (x, y, level) = map.spot_in(&strike.crime_scene, &mut rng, &mut occupied);
+
+        entities
+            .build_entity()
+            .with(Position { x, y, level }, &mut positions)
+            .with(
+                Renderable {
+                    glyph: rltk::to_cp437('V'),
+                    fg: strike.clue.color,
+                    bg: RGB::named(rltk::BLACK),
+                },
+                &mut renderables,
+            )
+            .with(strike.clue, &mut clues)
+            .build();
+    }
+}
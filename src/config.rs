@@ -0,0 +1,111 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(PartialEq, Clone, Copy, Debug, Serialize, Deserialize)]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    pub fn next(self) -> Self {
+        match self {
+            Difficulty::Easy => Difficulty::Normal,
+            Difficulty::Normal => Difficulty::Hard,
+            Difficulty::Hard => Difficulty::Easy,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Normal => "Normal",
+            Difficulty::Hard => "Hard",
+        }
+    }
+}
+
+#[derive(PartialEq, Clone, Debug)]
+pub struct CaseConfig {
+    pub difficulty: Difficulty,
+    pub suspect_count: usize,
+    pub clue_count: usize,
+    pub red_herrings: usize,
+    pub lent_weapon_chance: i32,
+    pub locked_room_chance: i32,
+    pub accomplice_chance: i32,
+    pub evidence_traits: usize,
+    pub witness_count: usize,
+    pub witness_error_chance: i32,
+    pub required_evidence: usize,
+    pub coroner_delay: i32,
+    pub serial_chance: i32,
+    pub max_strikes: usize,
+    pub strike_delay: i32,
+    pub killer_lie_chance: i32,
+    pub innocent_lie_chance: i32,
+}
+
+impl CaseConfig {
+    pub fn new(difficulty: Difficulty) -> Self {
+        match difficulty {
+            Difficulty::Easy => CaseConfig {
+                difficulty,
+                suspect_count: 3,
+                clue_count: 3,
+                red_herrings: 0,
+                lent_weapon_chance: 0,
+                locked_room_chance: 0,
+                accomplice_chance: 0,
+                evidence_traits: 2,
+                witness_count: 1,
+                witness_error_chance: 0,
+                required_evidence: 1,
+                coroner_delay: 10,
+                serial_chance: 0,
+                max_strikes: 0,
+                strike_delay: 0,
+                killer_lie_chance: 0,
+                innocent_lie_chance: 0,
+            },
+            Difficulty::Normal => CaseConfig {
+                difficulty,
+                suspect_count: 3,
+                clue_count: 3,
+                red_herrings: 1,
+                lent_weapon_chance: 25,
+                locked_room_chance: 50,
+                accomplice_chance: 0,
+                evidence_traits: 2,
+                witness_count: 1,
+                witness_error_chance: 25,
+                required_evidence: 3,
+                coroner_delay: 20,
+                serial_chance: 30,
+                max_strikes: 1,
+                strike_delay: 90,
+                killer_lie_chance: 50,
+                innocent_lie_chance: 15,
+            },
+            Difficulty::Hard => CaseConfig {
+                difficulty,
+                suspect_count: 5,
+                clue_count: 5,
+                red_herrings: 3,
+                lent_weapon_chance: 50,
+                locked_room_chance: 75,
+                accomplice_chance: 50,
+                evidence_traits: 3,
+                witness_count: 2,
+                witness_error_chance: 40,
+                required_evidence: 5,
+                coroner_delay: 30,
+                serial_chance: 60,
+                max_strikes: 2,
+                strike_delay: 60,
+                killer_lie_chance: 75,
+                innocent_lie_chance: 30,
+            },
+        }
+    }
+}
@@ -4,6 +4,10 @@ pub const SCREEN_HEIGHT: i32 = 60;
 pub const MAP_WIDTH: i32 = 85;
 pub const MAP_HEIGHT: i32 = 45;
 
+pub const VIEW_RADIUS: i32 = 12;
+pub const NIGHT_VIEW_RADIUS: i32 = 4;
+pub const LAMP_CHANCE: i32 = 50;
+
 pub const SIDEBAR_WIDTH: i32 = SCREEN_WIDTH - MAP_WIDTH;
 pub const SIDEBAR_HEIGHT: i32 = MAP_HEIGHT;
 
@@ -18,3 +22,11 @@ pub const EXAM_PANEL_HEIGHT: i32 = MAP_HEIGHT;
 
 pub const NOTES_PANEL_WIDTH: i32 = MAP_WIDTH;
 pub const NOTES_PANEL_HEIGHT: i32 = MAP_HEIGHT;
+pub const NOTES_PER_PAGE: usize = ((NOTES_PANEL_HEIGHT - 7) / 2) as usize;
+
+pub const MAX_CASE_NUMBER: u64 = 100_000_000;
+pub const MAP_SALT: u64 = 0x6e6f6972;
+pub const MAX_GENERATION_ATTEMPTS: i32 = 100;
+pub const MAX_ROOM_ATTEMPTS: i32 = 1000;
+pub const CASES_DIR: &str = "cases";
+pub const PROFILE_PATH: &str = "profile.ron";
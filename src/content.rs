@@ -0,0 +1,282 @@
+use serde::Deserialize;
+
+use crate::config::{CaseConfig, Difficulty};
+
+const CONTENT: &str = include_str!("../data/content.ron");
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Content {
+    pub first_names: Vec<String>,
+    pub last_names: Vec<String>,
+    pub ages: (i32, i32),
+    pub evidence_traits: Vec<TraitDef>,
+    pub victim_display: Vec<String>,
+    pub weapons: Vec<WeaponDef>,
+    pub relationships: Vec<RelationshipDef>,
+    pub witness_roles: Vec<String>,
+    pub buildings: Vec<BuildingDef>,
+    pub hours_of_death: (i32, i32),
+    pub dialogue: Dialogue,
+    pub narrative: Narrative,
+}
+
+impl Content {
+    pub fn load() -> Self {
+        let content: Content = match ron::de::from_str(CONTENT) {
+            Ok(content) => content,
+            Err(e) => panic!("Failed to load data/content.ron: {}", e),
+        };
+
+        for difficulty in [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard].iter() {
+            if let Err(e) = content.validate(&CaseConfig::new(*difficulty)) {
+                panic!(
+                    "data/content.ron is missing content for {} cases: {}",
+                    difficulty.name(),
+                    e
+                );
+            }
+        }
+
+        content
+    }
+
+    /// Checks there is enough of everything for the generator to draw from
+    /// without running out halfway through a case.
+    fn validate(&self, config: &CaseConfig) -> Result<(), String> {
+        fn at_least(what: &str, have: usize, need: usize) -> Result<(), String> {
+            if have < need {
+                Err(format!("needs {} {}, but there are {}", need, what, have))
+            } else {
+                Ok(())
+            }
+        }
+
+        // The victim, suspects, witnesses and any later victims each use up a
+        // first and a last name.
+        let people = 1 + config.suspect_count + config.witness_count + config.max_strikes;
+        at_least("first names", self.first_names.len(), people)?;
+        at_least("last names", self.last_names.len(), people)?;
+        if self.ages.0 >= self.ages.1 {
+            return Err("needs a range of ages".to_string());
+        }
+
+        at_least(
+            "evidence traits",
+            self.evidence_traits.len(),
+            config.evidence_traits,
+        )?;
+        // Innocent suspects are redrawn until they stop matching the evidence.
+        for evidence_trait in self.evidence_traits.iter() {
+            at_least(
+                &format!("values of {}", evidence_trait.name),
+                evidence_trait.values.len(),
+                2,
+            )?;
+        }
+
+        // Every suspect has a different relationship with the victim.
+        at_least(
+            "relationships",
+            self.relationships.len(),
+            config.suspect_count,
+        )?;
+        at_least(
+            "witness roles",
+            self.witness_roles.len(),
+            config.witness_count,
+        )?;
+
+        at_least("buildings", self.buildings.len(), 1)?;
+        // Everyone but the killer has to have been somewhere else.
+        for building in self.buildings.iter() {
+            at_least(
+                &format!("rooms in the {}", building.name),
+                building.rooms.len(),
+                2,
+            )?;
+
+            let on_floors: Vec<&String> = building.floors.iter().flatten().collect();
+            for room in building.rooms.iter().chain(Some(&building.entrance)) {
+                if on_floors.iter().filter(|r| **r == room).count() != 1 {
+                    return Err(format!(
+                        "needs {} on exactly one floor of the {}",
+                        room, building.name
+                    ));
+                }
+            }
+        }
+        if self.hours_of_death.0 >= self.hours_of_death.1 {
+            return Err("needs a range of hours of death".to_string());
+        }
+
+        at_least("weapons", self.weapons.len(), config.clue_count)?;
+        for weapon in self.weapons.iter() {
+            at_least(
+                &format!("shops for the {}", weapon.name),
+                weapon.shops.len(),
+                1,
+            )?;
+            at_least(
+                &format!("lines for the {}", weapon.name),
+                weapon.display.len(),
+                1,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    pub fn weapon(&self, name: &str) -> &WeaponDef {
+        self.weapons
+            .iter()
+            .find(|weapon| weapon.name == name)
+            .unwrap()
+    }
+
+    pub fn evidence_trait(&self, name: &str) -> &TraitDef {
+        self.evidence_traits
+            .iter()
+            .find(|evidence_trait| evidence_trait.name == name)
+            .unwrap()
+    }
+
+    pub fn building(&self, name: &str) -> &BuildingDef {
+        self.buildings
+            .iter()
+            .find(|building| building.name == name)
+            .unwrap()
+    }
+
+    pub fn relationship(&self, name: &str) -> &RelationshipDef {
+        self.relationships
+            .iter()
+            .find(|relationship| relationship.name == name)
+            .unwrap()
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct WeaponDef {
+    pub name: String,
+    pub cause_of_death: (String, String),
+    pub wound: String,
+    pub traces: Vec<String>,
+    pub shops: Vec<String>,
+    pub display: Vec<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct TraitDef {
+    pub name: String,
+    pub values: Vec<String>,
+    pub found_on: TraceSource,
+    pub trace: String,
+    pub description: String,
+    pub question: (String, String),
+}
+
+impl TraitDef {
+    pub fn trace(&self, value: &str) -> String {
+        fill_template(&self.trace, &[("value", value)])
+    }
+
+    pub fn describe(&self, value: &str) -> String {
+        fill_template(&self.description, &[("value", value)])
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum TraceSource {
+    Victim,
+    Weapon,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct BuildingDef {
+    pub name: String,
+    pub entrance: String,
+    pub rooms: Vec<String>,
+    pub room_width: (i32, i32),
+    pub room_height: (i32, i32),
+    pub corridor_width: i32,
+    pub floors: Vec<Vec<String>>,
+}
+
+impl BuildingDef {
+    /// The floor the entrance is on.
+    pub fn ground_floor(&self) -> usize {
+        self.floors
+            .iter()
+            .position(|floor| floor.contains(&self.entrance))
+            .unwrap_or(0)
+    }
+
+    /// Whether another room is on the same floor as this one, so that stairs
+    /// can reach the floor without going through it.
+    pub fn shares_floor(&self, room: &str) -> bool {
+        !self
+            .floors
+            .iter()
+            .any(|floor| floor.len() == 1 && floor.iter().any(|r| r == room))
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct RelationshipDef {
+    pub name: String,
+    pub motive: String,
+    pub evidence: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Dialogue {
+    pub greeting: (String, String),
+    pub relationship: (String, String),
+    pub alibi: (String, String),
+    pub gossip: (String, String),
+    pub witness: WitnessLines,
+    pub bystander: BystanderLines,
+    pub provenance: ProvenanceLines,
+    pub key: (String, String),
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct WitnessLines {
+    pub question: String,
+    pub saw: String,
+    pub together: String,
+    pub alone: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct BystanderLines {
+    pub greeting: (String, String),
+    pub sighting: (String, String),
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ProvenanceLines {
+    pub question: String,
+    pub owner: String,
+    pub lender: String,
+    pub none: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Narrative {
+    pub briefing: Vec<String>,
+    pub epilogue: Vec<String>,
+    pub accomplice: String,
+    pub strike: String,
+    pub proof: String,
+}
+
+pub fn fill_template(template: &str, fields: &[(&str, &str)]) -> String {
+    let mut filled = template.to_string();
+
+    for (key, value) in fields {
+        filled = filled.replace(&format!("{{{}}}", key), value);
+    }
+
+    filled
+}
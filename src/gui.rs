@@ -4,15 +4,21 @@ use std::collections::HashSet;
 use super::RunState;
 
 use super::State;
-use crate::components::ConversationAI;
+use crate::campaign::{ChapterStatus, CHAPTERS};
 use crate::components::ExamEntity;
 use crate::components::TalkEntity;
+use crate::config::CaseConfig;
 use crate::constants::*;
+use crate::narrative;
+use crate::profile::RANKS;
 use crate::story::Clue;
 use crate::story::Connection;
 use crate::story::Note;
 use crate::story::PlayerNotes;
+use crate::story::SerialKiller;
+use crate::story::Story;
 use crate::story::Suspect;
+use crate::story::Witness;
 use rltk::VirtualKeyCode;
 use rltk::{Console, Rltk, RGB};
 use specs::WorldExt;
@@ -20,6 +26,10 @@ use specs::WorldExt;
 #[derive(PartialEq, Clone, Copy)]
 pub enum MainMenuSelection {
     Play,
+    Campaign,
+    Career,
+    Difficulty,
+    CaseNumber,
     Quit,
 }
 
@@ -31,6 +41,7 @@ pub enum MainMenuResult {
 
 pub fn main_menu(gs: &mut State, ctx: &mut Rltk) -> MainMenuResult {
     let runstate = gs.ecs.fetch::<RunState>();
+    let difficulty = gs.ecs.fetch::<CaseConfig>().difficulty;
 
     let title = vec![
         "b.             8      ,o888888o.       8 8888   8 888888888o.   ".to_string(),
@@ -45,10 +56,8 @@ pub fn main_menu(gs: &mut State, ctx: &mut Rltk) -> MainMenuResult {
         "8            `Yo      `8888888P'       8 8888   8 8888     `88. ".to_string(),
     ];
 
-    let mut y = SCREEN_HEIGHT / 2 - 5;
-    for row in title {
+    for (y, row) in (SCREEN_HEIGHT / 2 - 5..).zip(title) {
         ctx.print_color_centered(y, RGB::named(rltk::WHITE), RGB::named(rltk::BLACK), &row);
-        y += 1;
     }
 
     if let RunState::MainMenu { selection } = *runstate {
@@ -57,29 +66,93 @@ pub fn main_menu(gs: &mut State, ctx: &mut Rltk) -> MainMenuResult {
                 SCREEN_HEIGHT / 2 + 8,
                 RGB::named(rltk::WHITE),
                 RGB::named(rltk::BLACK),
-                "> Play",
+                "> Quick Case",
             );
         } else {
             ctx.print_color_centered(
                 SCREEN_HEIGHT / 2 + 8,
                 RGB::named(rltk::WHITE),
                 RGB::named(rltk::BLACK),
-                "  Play",
+                "  Quick Case",
             );
         }
 
-        if selection == MainMenuSelection::Quit {
+        if selection == MainMenuSelection::Campaign {
             ctx.print_color_centered(
                 SCREEN_HEIGHT / 2 + 10,
                 RGB::named(rltk::WHITE),
                 RGB::named(rltk::BLACK),
-                "> Quit",
+                &format!("> Campaign ({}/{})", gs.campaign.solved, CHAPTERS.len()),
             );
         } else {
             ctx.print_color_centered(
                 SCREEN_HEIGHT / 2 + 10,
                 RGB::named(rltk::WHITE),
                 RGB::named(rltk::BLACK),
+                &format!("  Campaign ({}/{})", gs.campaign.solved, CHAPTERS.len()),
+            );
+        }
+
+        if selection == MainMenuSelection::Career {
+            ctx.print_color_centered(
+                SCREEN_HEIGHT / 2 + 12,
+                RGB::named(rltk::WHITE),
+                RGB::named(rltk::BLACK),
+                &format!("> Career: {}", gs.profile.rank().title),
+            );
+        } else {
+            ctx.print_color_centered(
+                SCREEN_HEIGHT / 2 + 12,
+                RGB::named(rltk::WHITE),
+                RGB::named(rltk::BLACK),
+                &format!("  Career: {}", gs.profile.rank().title),
+            );
+        }
+
+        if selection == MainMenuSelection::Difficulty {
+            ctx.print_color_centered(
+                SCREEN_HEIGHT / 2 + 14,
+                RGB::named(rltk::WHITE),
+                RGB::named(rltk::BLACK),
+                &format!("> Difficulty: {}", difficulty.name()),
+            );
+        } else {
+            ctx.print_color_centered(
+                SCREEN_HEIGHT / 2 + 14,
+                RGB::named(rltk::WHITE),
+                RGB::named(rltk::BLACK),
+                &format!("  Difficulty: {}", difficulty.name()),
+            );
+        }
+
+        if selection == MainMenuSelection::CaseNumber {
+            ctx.print_color_centered(
+                SCREEN_HEIGHT / 2 + 16,
+                RGB::named(rltk::WHITE),
+                RGB::named(rltk::BLACK),
+                "> Case #",
+            );
+        } else {
+            ctx.print_color_centered(
+                SCREEN_HEIGHT / 2 + 16,
+                RGB::named(rltk::WHITE),
+                RGB::named(rltk::BLACK),
+                "  Case #",
+            );
+        }
+
+        if selection == MainMenuSelection::Quit {
+            ctx.print_color_centered(
+                SCREEN_HEIGHT / 2 + 18,
+                RGB::named(rltk::WHITE),
+                RGB::named(rltk::BLACK),
+                "> Quit",
+            );
+        } else {
+            ctx.print_color_centered(
+                SCREEN_HEIGHT / 2 + 18,
+                RGB::named(rltk::WHITE),
+                RGB::named(rltk::BLACK),
                 "  Quit",
             );
         }
@@ -89,7 +162,11 @@ pub fn main_menu(gs: &mut State, ctx: &mut Rltk) -> MainMenuResult {
             Some(key) => match key {
                 rltk::VirtualKeyCode::Down => {
                     let newselection = match selection {
-                        MainMenuSelection::Play => MainMenuSelection::Quit,
+                        MainMenuSelection::Play => MainMenuSelection::Campaign,
+                        MainMenuSelection::Campaign => MainMenuSelection::Career,
+                        MainMenuSelection::Career => MainMenuSelection::Difficulty,
+                        MainMenuSelection::Difficulty => MainMenuSelection::CaseNumber,
+                        MainMenuSelection::CaseNumber => MainMenuSelection::Quit,
                         MainMenuSelection::Quit => MainMenuSelection::Play,
                     };
                     return MainMenuResult::NoSelection {
@@ -99,7 +176,11 @@ pub fn main_menu(gs: &mut State, ctx: &mut Rltk) -> MainMenuResult {
                 rltk::VirtualKeyCode::Up => {
                     let newselection = match selection {
                         MainMenuSelection::Play => MainMenuSelection::Quit,
-                        MainMenuSelection::Quit => MainMenuSelection::Play,
+                        MainMenuSelection::Campaign => MainMenuSelection::Play,
+                        MainMenuSelection::Career => MainMenuSelection::Campaign,
+                        MainMenuSelection::Difficulty => MainMenuSelection::Career,
+                        MainMenuSelection::CaseNumber => MainMenuSelection::Difficulty,
+                        MainMenuSelection::Quit => MainMenuSelection::CaseNumber,
                     };
                     return MainMenuResult::NoSelection {
                         selection: newselection,
@@ -118,22 +199,267 @@ pub fn main_menu(gs: &mut State, ctx: &mut Rltk) -> MainMenuResult {
     }
 }
 
+#[derive(PartialEq, Clone, Copy)]
+pub enum CaseNumberResult {
+    NoSelection { number: u64 },
+    Selection { number: u64 },
+    Cancel,
+}
+
+pub fn case_number_menu(ctx: &mut Rltk, number: u64) -> CaseNumberResult {
+    ctx.print_color_centered(
+        SCREEN_HEIGHT / 2 - 1,
+        RGB::named(rltk::WHITE),
+        RGB::named(rltk::BLACK),
+        "Enter a case number:",
+    );
+
+    ctx.print_color_centered(
+        SCREEN_HEIGHT / 2 + 1,
+        RGB::named(rltk::YELLOW),
+        RGB::named(rltk::BLACK),
+        &format!("#{}", number),
+    );
+
+    ctx.print_color_centered(
+        SCREEN_HEIGHT / 2 + 3,
+        RGB::named(rltk::WHITE),
+        RGB::named(rltk::BLACK),
+        "Use [0-9] to type. Press [Enter] to open the case or [Esc] to go back.",
+    );
+
+    let mut digit: Option<u64> = None;
+
+    match ctx.key {
+        None => {}
+        Some(key) => match key {
+            VirtualKeyCode::Key0 => digit = Some(0),
+            VirtualKeyCode::Key1 => digit = Some(1),
+            VirtualKeyCode::Key2 => digit = Some(2),
+            VirtualKeyCode::Key3 => digit = Some(3),
+            VirtualKeyCode::Key4 => digit = Some(4),
+            VirtualKeyCode::Key5 => digit = Some(5),
+            VirtualKeyCode::Key6 => digit = Some(6),
+            VirtualKeyCode::Key7 => digit = Some(7),
+            VirtualKeyCode::Key8 => digit = Some(8),
+            VirtualKeyCode::Key9 => digit = Some(9),
+            VirtualKeyCode::Back => {
+                return CaseNumberResult::NoSelection {
+                    number: number / 10,
+                };
+            }
+            VirtualKeyCode::Return => return CaseNumberResult::Selection { number },
+            VirtualKeyCode::Escape => return CaseNumberResult::Cancel,
+            _ => {}
+        },
+    }
+
+    if let Some(digit) = digit {
+        let new_number = number * 10 + digit;
+        if new_number < MAX_CASE_NUMBER {
+            return CaseNumberResult::NoSelection { number: new_number };
+        }
+    }
+
+    CaseNumberResult::NoSelection { number }
+}
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum CampaignResult {
+    NoSelection { selection: usize },
+    Selection { selection: usize },
+    Cancel,
+}
+
+pub fn campaign_menu(gs: &State, ctx: &mut Rltk, selection: usize) -> CampaignResult {
+    ctx.print_color_centered(
+        SCREEN_HEIGHT / 2 - 8,
+        RGB::named(rltk::WHITE),
+        RGB::named(rltk::BLACK),
+        "Campaign",
+    );
+
+    ctx.print_color_centered(
+        SCREEN_HEIGHT / 2 - 6,
+        RGB::named(rltk::GRAY),
+        RGB::named(rltk::BLACK),
+        &format!("{} of {} cases closed", gs.campaign.solved, CHAPTERS.len()),
+    );
+
+    let mut y = SCREEN_HEIGHT / 2 - 3;
+    for (i, chapter) in CHAPTERS.iter().enumerate() {
+        let (status, color) = match gs.campaign.status(i) {
+            ChapterStatus::Solved => ("Closed", rltk::GREEN),
+            ChapterStatus::Open => ("Open", rltk::YELLOW),
+            ChapterStatus::Locked => ("Locked", rltk::GRAY),
+        };
+        let cursor = if i == selection { ">" } else { " " };
+
+        ctx.print_color_centered(
+            y,
+            RGB::named(color),
+            RGB::named(rltk::BLACK),
+            &format!("{} {}. {} [{}]", cursor, i + 1, chapter.title, status),
+        );
+        y += 2;
+    }
+
+    ctx.print_color_centered(
+        y + 1,
+        RGB::named(rltk::WHITE),
+        RGB::named(rltk::BLACK),
+        "Use arrow keys to choose. Press [Enter] to open the case or [Esc] to go back.",
+    );
+
+    match ctx.key {
+        None => CampaignResult::NoSelection { selection },
+        Some(key) => match key {
+            VirtualKeyCode::Down => CampaignResult::NoSelection {
+                selection: (selection + 1) % CHAPTERS.len(),
+            },
+            VirtualKeyCode::Up => CampaignResult::NoSelection {
+                selection: (selection + CHAPTERS.len() - 1) % CHAPTERS.len(),
+            },
+            VirtualKeyCode::Return => {
+                if gs.campaign.status(selection) == ChapterStatus::Locked {
+                    CampaignResult::NoSelection { selection }
+                } else {
+                    CampaignResult::Selection { selection }
+                }
+            }
+            VirtualKeyCode::Escape => CampaignResult::Cancel,
+            _ => CampaignResult::NoSelection { selection },
+        },
+    }
+}
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum CareerResult {
+    NoSelection,
+    Cancel,
+}
+
+pub fn career_menu(gs: &State, ctx: &mut Rltk) -> CareerResult {
+    let profile = &gs.profile;
+
+    ctx.print_color_centered(
+        SCREEN_HEIGHT / 2 - 12,
+        RGB::named(rltk::WHITE),
+        RGB::named(rltk::BLACK),
+        &format!("{} of the city police", profile.rank().title),
+    );
+
+    let accuracy = match profile.accuracy() {
+        Some(accuracy) => format!("{}%", accuracy),
+        None => "-".to_string(),
+    };
+    let stats = [
+        format!("Cases closed: {}", profile.cases_closed),
+        format!("Wrong accusations: {}", profile.cases_failed),
+        format!("Accuracy: {}", accuracy),
+    ];
+
+    let mut y = SCREEN_HEIGHT / 2 - 9;
+    for line in stats.iter() {
+        ctx.print_color_centered(y, RGB::named(rltk::WHITE), RGB::named(rltk::BLACK), line);
+        y += 1;
+    }
+
+    if let Some(next) = profile.next_rank() {
+        ctx.print_color_centered(
+            y + 1,
+            RGB::named(rltk::GRAY),
+            RGB::named(rltk::BLACK),
+            &format!(
+                "Close {} more to make {}.",
+                next.cases_closed - profile.cases_closed,
+                next.title
+            ),
+        );
+    }
+
+    y += 4;
+    for rank in RANKS.iter() {
+        if let Some(tool) = rank.unlocks {
+            let (color, text) = if profile.cases_closed >= rank.cases_closed {
+                (
+                    rltk::HOTPINK,
+                    format!("{}: {}", tool.name(), tool.description()),
+                )
+            } else {
+                (
+                    rltk::GRAY,
+                    format!("{}: unlocked at {}", tool.name(), rank.title),
+                )
+            };
+            ctx.print_color_centered(y, RGB::named(color), RGB::named(rltk::BLACK), &text);
+            y += 2;
+        }
+    }
+
+    ctx.print_color_centered(
+        y + 1,
+        RGB::named(rltk::WHITE),
+        RGB::named(rltk::BLACK),
+        "Press [Esc] to go back.",
+    );
+
+    match ctx.key {
+        Some(VirtualKeyCode::Escape) => CareerResult::Cancel,
+        _ => CareerResult::NoSelection,
+    }
+}
+
 #[derive(PartialEq, Clone, Copy)]
 pub enum GameOverResult {
     None,
     ReturnToMain,
 }
 
-pub fn game_over(ctx: &mut Rltk) -> GameOverResult {
+pub fn game_over(gs: &mut State, ctx: &mut Rltk) -> GameOverResult {
+    let accusation = gs.ecs.fetch::<Accusation>();
+
+    let mut y = SCREEN_HEIGHT / 2 - 1;
+
+    if accusation.submitted {
+        let story = gs.ecs.fetch::<Story>();
+        let (verdict, color) = if accusation.is_correct(&story) {
+            ("Case closed. Justice has been served.", rltk::GREEN)
+        } else if accusation.names_culprits(&story) {
+            (
+                "You had the right people, but the case against them fell apart.",
+                rltk::RED,
+            )
+        } else {
+            (
+                "You accused the wrong people. The real culprits walk free.",
+                rltk::RED,
+            )
+        };
+
+        ctx.print_color_centered(4, RGB::named(color), RGB::named(rltk::BLACK), verdict);
+
+        for (line_y, line) in (7..).zip(narrative::epilogue(&story, &gs.content)) {
+            ctx.print_color_centered(
+                line_y,
+                RGB::named(rltk::WHITE),
+                RGB::named(rltk::BLACK),
+                &line,
+            );
+        }
+
+        y = SCREEN_HEIGHT - 6;
+    }
+
     ctx.print_color_centered(
-        SCREEN_HEIGHT / 2 - 1,
+        y,
         RGB::named(rltk::WHITE),
         RGB::named(rltk::BLACK),
         "Thank you for playing.",
     );
 
     ctx.print_color_centered(
-        SCREEN_HEIGHT / 2 + 1,
+        y + 2,
         RGB::named(rltk::WHITE),
         RGB::named(rltk::BLACK),
         "Press any key to return to the main menu.",
@@ -218,6 +544,12 @@ impl Time {
         self.minute = minute;
     }
 
+    /// Skips ahead to the start of the next working day.
+    pub fn next_morning(&mut self) {
+        self.increment_day();
+        self.set_time(8, 0);
+    }
+
     pub fn advance_hour(&mut self) {
         self.hour += 1;
         if self.hour > 23 {
@@ -234,6 +566,23 @@ impl Time {
         }
     }
 
+    /// How light it is outside, from 0.0 at night to 1.0 in the day. The sun
+    /// comes up between 5:00 and 7:00 and goes down between 19:00 and 21:00.
+    pub fn daylight(&self) -> f32 {
+        let minutes = (self.hour * 60 + self.minute) as f32;
+        let risen = |hour: f32| ((minutes - hour * 60.0) / 120.0).clamp(0.0, 1.0);
+
+        risen(5.0) - risen(19.0)
+    }
+
+    pub fn is_night(&self) -> bool {
+        self.daylight() < 0.5
+    }
+
+    pub fn total_minutes(&self) -> i32 {
+        (self.day * 24 + self.hour) * 60 + self.minute
+    }
+
     pub fn get_day(&self) -> i32 {
         self.day
     }
@@ -345,9 +694,11 @@ impl Message {
     pub fn color(&self) -> RGB {
         self.color
     }
+}
 
-    pub fn to_string(&self) -> String {
-        format!("{} | {}: {}", self.timestamp, self.speaker, self.content)
+impl std::fmt::Display for Message {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{} | {}: {}", self.timestamp, self.speaker, self.content)
     }
 }
 
@@ -482,11 +833,30 @@ pub fn draw_talk_panel(gs: &mut State, ctx: &mut Rltk) -> RunState {
     let rect = rltk::Rect::with_size(0, 0, TALK_PANEL_WIDTH, TALK_PANEL_HEIGHT);
     draw_box(ctx, rect, RGB::named(rltk::WHITE));
 
-    let entity = gs.ecs.read_resource::<TalkEntity>();
-    let entity = (*entity).entity;
-
-    let mut speaker_store = gs.ecs.write_storage::<Suspect>();
-    let mut speaker = speaker_store.get_mut(entity).unwrap();
+    let entity = gs.ecs.read_resource::<TalkEntity>().entity;
+
+    let suspects = gs.ecs.read_storage::<Suspect>();
+    let witnesses = gs.ecs.read_storage::<Witness>();
+
+    let (name, age, color, role, convo_options) = match suspects.get(entity) {
+        Some(suspect) => (
+            suspect.name.clone(),
+            suspect.age,
+            suspect.color,
+            None,
+            suspect.convo_options.clone(),
+        ),
+        None => {
+            let witness = witnesses.get(entity).unwrap();
+            (
+                witness.name.clone(),
+                witness.age,
+                witness.color,
+                Some(witness.role.clone()),
+                witness.convo_options.clone(),
+            )
+        }
+    };
 
     let headshot = rltk::Rect::with_size(2, 2, 11, 11);
     draw_box(ctx, headshot, RGB::named(rltk::WHITE));
@@ -498,13 +868,7 @@ pub fn draw_talk_panel(gs: &mut State, ctx: &mut Rltk) -> RunState {
         RGB::named(rltk::BLACK),
         "Name:",
     );
-    ctx.print_color(
-        20,
-        3,
-        RGB::named(speaker.color),
-        RGB::named(rltk::BLACK),
-        &speaker.name,
-    );
+    ctx.print_color(20, 3, RGB::named(color), RGB::named(rltk::BLACK), &name);
 
     ctx.print_color(
         14,
@@ -516,11 +880,22 @@ pub fn draw_talk_panel(gs: &mut State, ctx: &mut Rltk) -> RunState {
     ctx.print_color(
         19,
         6,
-        RGB::named(speaker.color),
+        RGB::named(color),
         RGB::named(rltk::BLACK),
-        &format!("{}", speaker.age),
+        &format!("{}", age),
     );
 
+    if let Some(role) = role {
+        ctx.print_color(
+            14,
+            9,
+            RGB::named(rltk::WHITE),
+            RGB::named(rltk::BLACK),
+            "Role:",
+        );
+        ctx.print_color(20, 9, RGB::named(color), RGB::named(rltk::BLACK), &role);
+    }
+
     ctx.set(0, 14, RGB::named(rltk::WHITE), RGB::named(rltk::BLACK), 195);
     ctx.set(
         TALK_PANEL_WIDTH - 1,
@@ -559,9 +934,7 @@ pub fn draw_talk_panel(gs: &mut State, ctx: &mut Rltk) -> RunState {
         );
     }
 
-    let mut ai_store = gs.ecs.write_storage::<ConversationAI>();
-    let mut ai = ai_store.get_mut(entity).unwrap();
-    let options = generate_conversation_options(&speaker, *ai);
+    let options = generate_conversation_options(&convo_options);
 
     let mut y = 16;
     for option in &options {
@@ -580,7 +953,7 @@ pub fn draw_talk_panel(gs: &mut State, ctx: &mut Rltk) -> RunState {
         TALK_PANEL_HEIGHT - 2,
         RGB::named(rltk::WHITE),
         RGB::named(rltk::BLACK),
-        "Use [0-9] to talk to the suspect. Press [Esc] to leave the conversation.",
+        "Use [0-9] to ask a question. Press [Esc] to leave the conversation.",
     );
 
     let mut idx: Option<usize> = None;
@@ -605,8 +978,7 @@ pub fn draw_talk_panel(gs: &mut State, ctx: &mut Rltk) -> RunState {
         },
     }
 
-    if idx.is_some() {
-        let idx = idx.unwrap();
+    if let Some(idx) = idx {
         let mut time = gs.ecs.write_resource::<Time>();
         let mut log = gs.ecs.write_resource::<Log>();
         log.log_message(
@@ -615,12 +987,7 @@ pub fn draw_talk_panel(gs: &mut State, ctx: &mut Rltk) -> RunState {
             options[idx].0.as_str(),
             RGB::named(rltk::WHITE),
         );
-        log.log_message(
-            &time,
-            &speaker.name,
-            options[idx].1.as_str(),
-            RGB::named(speaker.color),
-        );
+        log.log_message(&time, &name, options[idx].1.as_str(), RGB::named(color));
 
         time.advance_minute();
 
@@ -635,12 +1002,11 @@ pub fn draw_talk_panel(gs: &mut State, ctx: &mut Rltk) -> RunState {
 }
 
 fn generate_conversation_options(
-    speaker: &Suspect,
-    ai: ConversationAI,
+    convo_options: &[(String, String, Option<Note>)],
 ) -> Vec<(String, String, Option<Note>)> {
     let mut options = vec![];
 
-    for option in speaker.convo_options.clone() {
+    for option in convo_options.iter() {
         options.push(option.clone());
     }
 
@@ -651,8 +1017,7 @@ pub fn draw_examination_panel(gs: &mut State, ctx: &mut Rltk) -> RunState {
     let rect = rltk::Rect::with_size(0, 0, EXAM_PANEL_WIDTH, EXAM_PANEL_HEIGHT);
     draw_box(ctx, rect, RGB::named(rltk::WHITE));
 
-    let entity = gs.ecs.read_resource::<ExamEntity>();
-    let entity = (*entity).entity;
+    let entity = gs.ecs.read_resource::<ExamEntity>().entity;
 
     let mut clue_store = gs.ecs.write_storage::<Clue>();
     let clue = clue_store.get_mut(entity).unwrap();
@@ -752,14 +1117,8 @@ pub fn draw_examination_panel(gs: &mut State, ctx: &mut Rltk) -> RunState {
         }
     }
 
-    match ctx.key {
-        None => {}
-        Some(key) => match key {
-            VirtualKeyCode::Escape => {
-                return RunState::AwaitingInput;
-            }
-            _ => {}
-        },
+    if ctx.key == Some(VirtualKeyCode::Escape) {
+        return RunState::AwaitingInput;
     }
 
     RunState::Examining
@@ -767,8 +1126,8 @@ pub fn draw_examination_panel(gs: &mut State, ctx: &mut Rltk) -> RunState {
 
 fn display_clue(ctx: &mut Rltk, clue: &Clue) {
     let display = clue.display.clone();
-    let mut y = EXAM_PANEL_HEIGHT / 2 - display.len() as i32 / 2;
-    for row in display {
+    let top = EXAM_PANEL_HEIGHT / 2 - display.len() as i32 / 2;
+    for (y, row) in (top..).zip(display) {
         ctx.print_color(
             EXAM_PANEL_WIDTH / 2 - row.len() as i32 / 2,
             y,
@@ -776,7 +1135,6 @@ fn display_clue(ctx: &mut Rltk, clue: &Clue) {
             RGB::named(rltk::BLACK),
             &row,
         );
-        y += 1;
     }
 
     for marker in clue.markers.clone() {
@@ -902,18 +1260,30 @@ impl NoteBoxes {
     pub fn contains_id(&self, id: u32) -> bool {
         self.boxes.contains(&id)
     }
+
+    pub fn matches(&self, ids: &[u32]) -> bool {
+        ids.len() == self.boxes.len() && ids.iter().all(|id| self.contains_id(*id))
+    }
 }
 
-pub fn draw_notes(gs: &mut State, ctx: &mut Rltk) -> RunState {
+pub fn draw_notes(gs: &mut State, ctx: &mut Rltk, page: usize) -> RunState {
     let rect = rltk::Rect::with_size(0, 0, NOTES_PANEL_WIDTH, NOTES_PANEL_HEIGHT);
     draw_box(ctx, rect, RGB::named(rltk::WHITE));
 
+    let mut notes = gs.ecs.write_resource::<PlayerNotes>();
+
+    // Notes are kept in the order they were written down, a page at a time.
+    let mut sorted: Vec<Note> = notes.notes.iter().cloned().collect();
+    sorted.sort_by_key(|note| note.id);
+    let last_page = sorted.len().saturating_sub(1) / NOTES_PER_PAGE;
+    let page = page.min(last_page);
+
     ctx.print_color(
         1,
         1,
         RGB::named(rltk::WHITE),
         RGB::named(rltk::BLACK),
-        "Notes",
+        &format!("Notes (page {} of {})", page + 1, last_page + 1),
     );
 
     ctx.set(0, 2, RGB::named(rltk::WHITE), RGB::named(rltk::BLACK), 195);
@@ -934,7 +1304,7 @@ pub fn draw_notes(gs: &mut State, ctx: &mut Rltk) -> RunState {
         NOTES_PANEL_HEIGHT - 2,
         RGB::named(rltk::WHITE),
         RGB::named(rltk::BLACK),
-        "Use [Up] and [Down] to scroll through notes. Press [Esc] to leave notes.",
+        "Click notes to select, [Enter] to connect, [Up]/[Down] to page, [Esc] to leave.",
     );
 
     ctx.set(
@@ -966,20 +1336,16 @@ pub fn draw_notes(gs: &mut State, ctx: &mut Rltk) -> RunState {
 
     let mut click_zones: Vec<(i32, i32, i32, u32)> = vec![];
 
-    let mut notes = gs.ecs.write_resource::<PlayerNotes>();
-
     let mut y = 4;
-    for note in notes.notes.iter() {
+    for note in sorted
+        .iter()
+        .skip(page * NOTES_PER_PAGE)
+        .take(NOTES_PER_PAGE)
+    {
         ctx.print_color(2, y, RGB::named(rltk::WHITE), RGB::named(rltk::BLACK), "o");
         let mut x = 4;
         for pair in note.note.clone() {
-            ctx.print_color(
-                x,
-                y,
-                RGB::named(pair.1),
-                RGB::named(rltk::BLACK),
-                &format!("{}", pair.0),
-            );
+            ctx.print_color(x, y, RGB::named(pair.1), RGB::named(rltk::BLACK), &pair.0);
             if pair.2 {
                 click_zones.push((x, y, pair.0.len() as i32, note.id));
                 if note_boxes.contains_id(note.id) {
@@ -1016,14 +1382,14 @@ pub fn draw_notes(gs: &mut State, ctx: &mut Rltk) -> RunState {
 
     let mut cxns = gs.ecs.write_resource::<Vec<Connection>>();
 
-    if note_boxes.boxes.len() >= 2 {
+    if ctx.key == Some(VirtualKeyCode::Return) && note_boxes.boxes.len() >= 2 {
         let mut time = gs.ecs.write_resource::<Time>();
         let mut log = gs.ecs.write_resource::<Log>();
         let mut cxn_found = false;
 
         for i in 0..cxns.len() {
             let cxn = cxns[i].clone();
-            if note_boxes.contains_id(cxn.ids.0) && note_boxes.contains_id(cxn.ids.1) {
+            if note_boxes.matches(&cxn.ids) {
                 cxn_found = true;
                 let new_note = cxn.note;
                 log.log_message(
@@ -1035,14 +1401,41 @@ pub fn draw_notes(gs: &mut State, ctx: &mut Rltk) -> RunState {
                 notes.add_note(new_note);
                 cxns.remove(i);
 
-                let mut options = gs.ecs.write_resource::<Options>();
+                let deductions = gs.ecs.fetch::<Story>().connections.len() - cxns.len();
+                let required = gs.ecs.fetch::<CaseConfig>().required_evidence;
+
+                if deductions >= required || cxns.is_empty() {
+                    let mut options = gs.ecs.write_resource::<Options>();
 
-                options.add_option('A', "Accuse");
+                    options.add_option('A', "Accuse");
+                }
 
                 break;
             }
         }
 
+        if !cxn_found {
+            let selected: Vec<Note> = notes
+                .notes
+                .iter()
+                .filter(|note| note_boxes.contains_id(note.id))
+                .cloned()
+                .collect();
+
+            if selected.len() == 2 {
+                if let Some(new_note) = notes.catch_lie(&selected[0], &selected[1]) {
+                    cxn_found = true;
+                    log.log_message(
+                        &time,
+                        "You",
+                        new_note.get_log_msg().as_str(),
+                        RGB::named(rltk::GREEN),
+                    );
+                    notes.add_note(new_note);
+                }
+            }
+        }
+
         if !cxn_found {
             log.log_message(
                 &time,
@@ -1063,11 +1456,90 @@ pub fn draw_notes(gs: &mut State, ctx: &mut Rltk) -> RunState {
             VirtualKeyCode::Escape => {
                 return RunState::AwaitingInput;
             }
+            VirtualKeyCode::Up => {
+                return RunState::Notes {
+                    page: page.saturating_sub(1),
+                };
+            }
+            VirtualKeyCode::Down => {
+                return RunState::Notes {
+                    page: (page + 1).min(last_page),
+                };
+            }
             _ => {}
         },
     }
 
-    RunState::Notes
+    RunState::Notes { page }
+}
+
+pub struct Accusation {
+    pub killer: Option<String>,
+    pub accomplice: Option<String>,
+    pub weapon: Option<String>,
+    pub motive: Option<String>,
+    pub submitted: bool,
+}
+
+impl Accusation {
+    pub fn new() -> Self {
+        Accusation {
+            killer: None,
+            accomplice: None,
+            weapon: None,
+            motive: None,
+            submitted: false,
+        }
+    }
+
+    /// Whether the right people were accused, whatever the case against them.
+    pub fn names_culprits(&self, story: &Story) -> bool {
+        let killer = story.suspects.iter().find(|s| s.is_killer);
+        let accomplice = story.suspects.iter().find(|s| s.is_accomplice);
+
+        self.killer.as_ref() == killer.map(|s| &s.name)
+            && self.accomplice.as_ref() == accomplice.map(|s| &s.name)
+    }
+
+    pub fn is_correct(&self, story: &Story) -> bool {
+        let killer = story.suspects.iter().find(|s| s.is_killer);
+
+        self.names_culprits(story)
+            && self.weapon.as_ref() == Some(&story.victim.weapon_used)
+            && self.motive.as_ref() == killer.map(|s| &s.motive)
+    }
+}
+
+/// Prints a row of choices, wrapping onto the next line when it runs out of
+/// room, and returns the click zones for each one along with the next free y.
+fn draw_choices(
+    ctx: &mut Rltk,
+    y: i32,
+    choices: &[(String, bool)],
+) -> (Vec<(i32, i32, i32, usize)>, i32) {
+    let mut click_zones = vec![];
+    let mut x = 4;
+    let mut y = y;
+
+    for (i, (choice, selected)) in choices.iter().enumerate() {
+        let width = choice.len() as i32;
+        if x + width > SCREEN_WIDTH - 2 {
+            x = 4;
+            y += 1;
+        }
+
+        ctx.print_color(
+            x,
+            y,
+            RGB::named(if *selected { rltk::YELLOW } else { rltk::GRAY }),
+            RGB::named(rltk::BLACK),
+            choice,
+        );
+        click_zones.push((x, y, width, i));
+        x += width + 3;
+    }
+
+    (click_zones, y + 1)
 }
 
 pub fn draw_accuse_panel(gs: &mut State, ctx: &mut Rltk) -> RunState {
@@ -1087,57 +1559,150 @@ pub fn draw_accuse_panel(gs: &mut State, ctx: &mut Rltk) -> RunState {
         SCREEN_HEIGHT - 2,
         RGB::named(rltk::WHITE),
         RGB::named(rltk::BLACK),
-        "Use your mouse to answer the questions. Press [Esc] to leave.",
+        "Use your mouse to answer the questions. Press [Enter] to accuse or [Esc] to leave.",
     );
 
+    let story = gs.ecs.fetch::<Story>();
+    let mut accusation = gs.ecs.write_resource::<Accusation>();
+
+    let dead = gs.ecs.fetch::<SerialKiller>().dead_suspects();
+    let names: Vec<String> = story
+        .suspects
+        .iter()
+        .filter(|s| !dead.contains(&s.name))
+        .map(|s| s.name.to_string())
+        .collect();
+
     ctx.print_color(
         2,
-        5,
+        4,
         RGB::named(rltk::WHITE),
         RGB::named(rltk::BLACK),
         "Who is the killer?",
     );
 
+    let killers: Vec<(String, bool)> = names
+        .iter()
+        .map(|name| (name.to_string(), accusation.killer.as_ref() == Some(name)))
+        .collect();
+    let (killer_zones, _) = draw_choices(ctx, 5, &killers);
+
     ctx.print_color(
         2,
-        8,
+        7,
         RGB::named(rltk::WHITE),
         RGB::named(rltk::BLACK),
-        "What is the murder weapon?",
+        "Did anyone help them?",
     );
 
+    let mut accomplices: Vec<(String, bool)> =
+        vec![("Nobody".to_string(), accusation.accomplice.is_none())];
+    for name in names.iter() {
+        accomplices.push((
+            name.to_string(),
+            accusation.accomplice.as_ref() == Some(name),
+        ));
+    }
+    let (accomplice_zones, _) = draw_choices(ctx, 8, &accomplices);
+
     ctx.print_color(
         2,
         11,
         RGB::named(rltk::WHITE),
         RGB::named(rltk::BLACK),
-        "Provide one piece of evidence: ",
+        "What is the murder weapon?",
     );
 
+    let weapons: Vec<String> = story.clues.iter().map(|c| c.name.to_string()).collect();
+    let weapon_choices: Vec<(String, bool)> = weapons
+        .iter()
+        .map(|weapon| {
+            (
+                weapon.to_string(),
+                accusation.weapon.as_ref() == Some(weapon),
+            )
+        })
+        .collect();
+    let (weapon_zones, _) = draw_choices(ctx, 12, &weapon_choices);
+
     ctx.print_color(
         2,
         14,
         RGB::named(rltk::WHITE),
         RGB::named(rltk::BLACK),
-        "Provide another piece of evidence: ",
+        "What was the motive?",
     );
 
-    ctx.set(0, 17, RGB::named(rltk::WHITE), RGB::named(rltk::BLACK), 195);
+    let mut motives: Vec<String> = vec![];
+    for suspect in story.suspects.iter() {
+        if !motives.contains(&suspect.motive) {
+            motives.push(suspect.motive.to_string());
+        }
+    }
+    let motive_choices: Vec<(String, bool)> = motives
+        .iter()
+        .map(|motive| {
+            (
+                motive.to_string(),
+                accusation.motive.as_ref() == Some(motive),
+            )
+        })
+        .collect();
+    let (motive_zones, _) = draw_choices(ctx, 15, &motive_choices);
+
+    if ctx.left_click {
+        let (mouse_x, mouse_y) = ctx.mouse_pos();
+        let clicked = |zones: &[(i32, i32, i32, usize)]| {
+            zones
+                .iter()
+                .find(|(x, y, w, _)| mouse_y == *y && (*x..*x + *w).contains(&mouse_x))
+                .map(|(_, _, _, i)| *i)
+        };
+
+        if let Some(i) = clicked(&killer_zones) {
+            accusation.killer = Some(names[i].to_string());
+        }
+        if let Some(i) = clicked(&accomplice_zones) {
+            accusation.accomplice = if i == 0 {
+                None
+            } else {
+                Some(names[i - 1].to_string())
+            };
+        }
+        if let Some(i) = clicked(&weapon_zones) {
+            accusation.weapon = Some(weapons[i].to_string());
+        }
+        if let Some(i) = clicked(&motive_zones) {
+            accusation.motive = Some(motives[i].to_string());
+        }
+    }
+
+    ctx.set(0, 20, RGB::named(rltk::WHITE), RGB::named(rltk::BLACK), 195);
     ctx.set(
         SCREEN_WIDTH - 1,
-        17,
+        20,
         RGB::named(rltk::WHITE),
         RGB::named(rltk::BLACK),
         180,
     );
 
     for x in 1..SCREEN_WIDTH - 1 {
-        ctx.set(x, 17, RGB::named(rltk::WHITE), RGB::named(rltk::BLACK), 196);
+        ctx.set(x, 20, RGB::named(rltk::WHITE), RGB::named(rltk::BLACK), 196);
     }
 
     match ctx.key {
         None => {}
         Some(key) => match key {
+            VirtualKeyCode::Return
+                if accusation.killer.is_some()
+                    && accusation.weapon.is_some()
+                    && accusation.motive.is_some() =>
+            {
+                accusation.submitted = true;
+                return RunState::GameOver {
+                    result: GameOverResult::None,
+                };
+            }
             VirtualKeyCode::Escape => {
                 return RunState::AwaitingInput;
             }
@@ -1147,3 +1712,60 @@ pub fn draw_accuse_panel(gs: &mut State, ctx: &mut Rltk) -> RunState {
 
     RunState::Accuse
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Difficulty;
+    use crate::content::Content;
+    use rltk::RandomNumberGenerator;
+
+    fn boxes(ids: &[u32]) -> NoteBoxes {
+        let mut boxes = NoteBoxes::new();
+        for id in ids.iter() {
+            boxes.add_box(*id);
+        }
+        boxes
+    }
+
+    #[test]
+    fn boxes_match_the_exact_set_in_any_order() {
+        assert!(boxes(&[1, 2, 3]).matches(&[3, 1, 2]));
+    }
+
+    #[test]
+    fn boxes_do_not_match_a_subset_or_superset() {
+        assert!(!boxes(&[1, 2, 3]).matches(&[1, 2]));
+        assert!(!boxes(&[1, 2]).matches(&[1, 2, 3]));
+        assert!(!boxes(&[1, 2]).matches(&[1, 3]));
+    }
+
+    #[test]
+    fn accusations_need_both_culprits_weapon_and_motive() {
+        let content = Content::load();
+        let config = CaseConfig::new(Difficulty::Hard);
+        let story = (0..)
+            .map(|seed| {
+                let mut rng = RandomNumberGenerator::seeded(seed);
+                Story::gen_rand(seed, &mut rng, &content, &config)
+            })
+            .find(|story| story.suspects.iter().any(|s| s.is_accomplice))
+            .unwrap();
+        let killer = story.suspects.iter().find(|s| s.is_killer).unwrap();
+        let accomplice = story.suspects.iter().find(|s| s.is_accomplice).unwrap();
+
+        let mut accusation = Accusation::new();
+        accusation.killer = Some(killer.name.to_string());
+        accusation.weapon = Some(story.victim.weapon_used.to_string());
+        accusation.motive = Some(killer.motive.to_string());
+        assert!(!accusation.names_culprits(&story));
+        assert!(!accusation.is_correct(&story));
+
+        accusation.accomplice = Some(accomplice.name.to_string());
+        assert!(accusation.is_correct(&story));
+
+        accusation.motive = None;
+        assert!(accusation.names_culprits(&story));
+        assert!(!accusation.is_correct(&story));
+    }
+}
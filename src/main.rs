@@ -1,9 +1,16 @@
+mod campaign;
+mod case_file;
 mod components;
+mod config;
 mod constants;
+mod content;
 mod gui;
 mod map;
+mod narrative;
 mod player;
+mod profile;
 mod story;
+mod verifier;
 
 use std::collections::BTreeMap;
 
@@ -13,22 +20,30 @@ use gui::{
     draw_accuse_panel, draw_examination_panel, draw_log, draw_notes, draw_sidebar, draw_talk_panel,
     view_log, GameOverResult, MainMenuSelection, PauseMenuSelection,
 };
-use rltk::{Console, GameState, Rltk, RGB};
+use rltk::{Console, GameState, RandomNumberGenerator, Rltk, RGB};
 use specs::prelude::*;
 
 use crate::{
-    gui::{Log, NoteBoxes, Options, Time},
+    campaign::{Campaign, CHAPTERS},
+    case_file::CaseFile,
+    config::{CaseConfig, Difficulty},
+    content::Content,
+    gui::{Accusation, Log, NoteBoxes, Options, Time},
     map::Map,
-    story::{Clue, PlayerNotes, Story, Suspect},
+    profile::Profile,
+    story::{Clue, CoronerReport, PlayerNotes, SerialKiller, Story, Suspect, Witness},
 };
 
 #[derive(PartialEq, Clone, Copy)]
 pub enum RunState {
     MainMenu { selection: MainMenuSelection },
+    CaseNumber { number: u64 },
+    Campaign { selection: usize },
+    Career,
     AwaitingInput,
     Talking,
     Examining,
-    Notes,
+    Notes { page: usize },
     Accuse,
     Log { page: usize },
     Paused { selection: PauseMenuSelection },
@@ -37,26 +52,72 @@ pub enum RunState {
 
 pub struct State {
     ecs: World,
+    content: Content,
+    difficulty: Difficulty,
+    campaign: Campaign,
+    profile: Profile,
 }
 
 impl State {
     fn run_systems(&mut self) {
+        let mut visibility_checker = VisibilityChecker {};
+        visibility_checker.run_now(&self.ecs);
         let mut conversation_checker = ConversationChecker {};
         conversation_checker.run_now(&self.ecs);
         let mut examination_checker = ExaminationChecker {};
         examination_checker.run_now(&self.ecs);
         let mut movement_checker = MovementChecker {};
         movement_checker.run_now(&self.ecs);
+        let mut coroner_checker = CoronerChecker {};
+        coroner_checker.run_now(&self.ecs);
+        let mut key_checker = KeyChecker {};
+        key_checker.run_now(&self.ecs);
+        let mut serial_checker = SerialChecker {};
+        serial_checker.run_now(&self.ecs);
         self.ecs.maintain();
     }
 
-    fn new_game(&mut self) {
+    fn new_game(&mut self, seed: Option<u64>) {
+        let seed = seed.unwrap_or_else(|| RandomNumberGenerator::new().range(0, MAX_CASE_NUMBER));
+        let mut rng = RandomNumberGenerator::seeded(seed);
+
+        let config = CaseConfig::new(self.difficulty);
+        let story = Story::gen_rand(seed, &mut rng, &self.content, &config);
+
+        self.campaign.current = None;
+        self.open_case(story, config);
+    }
+
+    fn load_chapter(&mut self, chapter: usize) -> Result<(), String> {
+        let case = CHAPTERS[chapter].load(&self.content)?;
+        self.load_case(case);
+        self.campaign.current = Some(chapter);
+
+        let time = self.ecs.fetch::<Time>().clone();
+        self.ecs.write_resource::<Log>().log_message(
+            &time,
+            "Game",
+            &format!("Chapter {}: {}", chapter + 1, CHAPTERS[chapter].title),
+            RGB::named(rltk::YELLOW),
+        );
+
+        Ok(())
+    }
+
+    /// Opens a saved case at the difficulty it was made for, without changing
+    /// the difficulty the player picked for their own cases.
+    fn load_case(&mut self, case: CaseFile) {
+        self.open_case(case.story, CaseConfig::new(case.difficulty));
+    }
+
+    fn open_case(&mut self, story: Story, mut config: CaseConfig) {
         self.ecs = World::new();
 
         self.ecs.register::<Position>();
         self.ecs.register::<Renderable>();
         self.ecs.register::<ConversationAI>();
         self.ecs.register::<Suspect>();
+        self.ecs.register::<Witness>();
         self.ecs.register::<Clue>();
         self.ecs.register::<MovementAI>();
 
@@ -64,15 +125,26 @@ impl State {
             selection: MainMenuSelection::Play,
         });
 
-        self.ecs.insert(PlayerPosition { x: 1, y: 1 });
         self.ecs.insert(Renderable {
             glyph: rltk::to_cp437('@'),
             fg: RGB::named(rltk::WHITE),
             bg: RGB::named(rltk::BLACK),
         });
 
-        let story = Story::gen_rand();
+        // The map gets its own stream off the seed, so a case opened from a
+        // file is laid out just like the one it was saved from.
+        let seed = story.seed;
+        let rng = RandomNumberGenerator::seeded(seed ^ MAP_SALT);
+        self.ecs.insert(rng);
+        self.profile.apply_tools(&mut config);
+        self.ecs.insert(config);
+
         let map = Map::from_story(&story, self);
+        self.ecs.insert(PlayerPosition {
+            x: map.start.0,
+            y: map.start.1,
+            level: map.start.2,
+        });
 
         self.ecs.insert(story);
         self.ecs.insert(map);
@@ -80,6 +152,8 @@ impl State {
         let note_boxes = NoteBoxes::new();
         self.ecs.insert(note_boxes);
 
+        self.ecs.insert(Accusation::new());
+
         let time = Time::new();
 
         let mut log = Log { log: vec![] };
@@ -95,11 +169,50 @@ impl State {
             "Use arrow keys to move around.",
             RGB::named(rltk::HOTPINK),
         );
+        log.log_message(
+            &time,
+            "Game",
+            &format!("You have been assigned case #{}.", seed),
+            RGB::named(rltk::WHITE),
+        );
+        for line in narrative::briefing(&self.ecs.fetch::<Story>(), &self.content) {
+            log.log_message(&time, "Chief", &line, RGB::named(rltk::LIGHTBLUE));
+        }
+        for tool in self.profile.tools() {
+            log.log_message(
+                &time,
+                tool.name(),
+                tool.description(),
+                RGB::named(rltk::HOTPINK),
+            );
+        }
         self.ecs.insert(log);
 
         let notes = PlayerNotes::new();
         self.ecs.insert(notes);
 
+        let report = CoronerReport::new(
+            &self.ecs.fetch::<Story>().victim,
+            time.total_minutes() + self.ecs.fetch::<CaseConfig>().coroner_delay,
+        );
+        self.ecs.insert(report);
+
+        let serial_killer = {
+            let story = self.ecs.fetch::<Story>();
+            let news = story
+                .strikes
+                .iter()
+                .map(|strike| narrative::strike(strike, &self.content))
+                .collect();
+            SerialKiller::new(
+                &story.strikes,
+                news,
+                time.total_minutes(),
+                self.ecs.fetch::<CaseConfig>().strike_delay,
+            )
+        };
+        self.ecs.insert(serial_killer);
+
         self.ecs.insert(time);
 
         let talk = TalkEntity {
@@ -135,8 +248,11 @@ impl GameState for State {
 
         match newrunstate {
             RunState::MainMenu { .. } => {}
+            RunState::CaseNumber { .. } => {}
+            RunState::Campaign { .. } => {}
+            RunState::Career => {}
             RunState::GameOver { .. } => {}
-            RunState::Accuse { .. } => {}
+            RunState::Accuse => {}
             RunState::Talking => {
                 draw_log(self, ctx);
                 draw_sidebar(self, ctx);
@@ -145,7 +261,7 @@ impl GameState for State {
                 draw_log(self, ctx);
                 draw_sidebar(self, ctx);
             }
-            RunState::Notes => {
+            RunState::Notes { .. } => {
                 draw_log(self, ctx);
                 draw_sidebar(self, ctx);
             }
@@ -154,7 +270,7 @@ impl GameState for State {
                 let player_render = self.ecs.fetch::<Renderable>();
 
                 let map = self.ecs.fetch::<Map>();
-                map.draw(ctx, player_pos.x, player_pos.y);
+                map.draw(ctx, player_pos.x, player_pos.y, player_pos.level);
 
                 ctx.set(
                     MAP_WIDTH / 2 + 1,
@@ -168,12 +284,34 @@ impl GameState for State {
                 let renderables = self.ecs.read_storage::<Renderable>();
 
                 for (pos, render) in (&positions, &renderables).join() {
-                    ctx.set(
-                        MAP_WIDTH / 2 + 1 + pos.x - player_pos.x,
-                        MAP_HEIGHT / 2 + 1 + pos.y - player_pos.y,
-                        render.fg,
-                        render.bg,
-                        render.glyph,
+                    if pos.level != player_pos.level || !map.is_visible(pos.x, pos.y, pos.level) {
+                        continue;
+                    }
+
+                    let screen_x = MAP_WIDTH / 2 + 1 + pos.x - player_pos.x;
+                    let screen_y = MAP_HEIGHT / 2 + 1 + pos.y - player_pos.y;
+
+                    if (0..MAP_WIDTH).contains(&screen_x) && (0..MAP_HEIGHT).contains(&screen_y) {
+                        ctx.set(screen_x, screen_y, render.fg, render.bg, render.glyph);
+                    }
+                }
+
+                if let Some(room) = map.room_at(player_pos.x, player_pos.y, player_pos.level) {
+                    ctx.print_color(
+                        1,
+                        1,
+                        RGB::named(rltk::WHITE),
+                        RGB::named(rltk::BLACK),
+                        &room.name,
+                    );
+                }
+                if map.levels.len() > 1 {
+                    ctx.print_color(
+                        1,
+                        2,
+                        RGB::named(rltk::GRAY),
+                        RGB::named(rltk::BLACK),
+                        &map.level_name(player_pos.level),
                     );
                 }
 
@@ -193,6 +331,26 @@ impl GameState for State {
                         MainMenuSelection::Play => {
                             newrunstate = RunState::AwaitingInput;
                         }
+                        MainMenuSelection::Campaign => {
+                            newrunstate = RunState::Campaign {
+                                selection: self.campaign.next_chapter(),
+                            };
+                        }
+                        MainMenuSelection::Career => {
+                            newrunstate = RunState::Career;
+                        }
+                        MainMenuSelection::Difficulty => {
+                            self.difficulty = self.difficulty.next();
+                            self.new_game(None);
+                            newrunstate = RunState::MainMenu {
+                                selection: MainMenuSelection::Difficulty,
+                            };
+                        }
+                        MainMenuSelection::CaseNumber => {
+                            newrunstate = RunState::CaseNumber {
+                                number: self.ecs.fetch::<Story>().seed,
+                            };
+                        }
                         MainMenuSelection::Quit => {
                             newrunstate = RunState::GameOver {
                                 result: GameOverResult::None,
@@ -201,6 +359,53 @@ impl GameState for State {
                     },
                 };
             }
+            RunState::CaseNumber { number } => {
+                let result = gui::case_number_menu(ctx, number);
+                match result {
+                    gui::CaseNumberResult::NoSelection { number } => {
+                        newrunstate = RunState::CaseNumber { number }
+                    }
+                    gui::CaseNumberResult::Selection { number } => {
+                        self.new_game(Some(number));
+                        newrunstate = RunState::AwaitingInput;
+                    }
+                    gui::CaseNumberResult::Cancel => {
+                        newrunstate = RunState::MainMenu {
+                            selection: MainMenuSelection::CaseNumber,
+                        }
+                    }
+                }
+            }
+            RunState::Campaign { selection } => {
+                let result = gui::campaign_menu(self, ctx, selection);
+                match result {
+                    gui::CampaignResult::NoSelection { selection } => {
+                        newrunstate = RunState::Campaign { selection }
+                    }
+                    gui::CampaignResult::Selection { selection } => {
+                        match self.load_chapter(selection) {
+                            Ok(()) => newrunstate = RunState::AwaitingInput,
+                            Err(e) => rltk::console::log(format!(
+                                "Could not open chapter {}: {}",
+                                selection + 1,
+                                e
+                            )),
+                        }
+                    }
+                    gui::CampaignResult::Cancel => {
+                        newrunstate = RunState::MainMenu {
+                            selection: MainMenuSelection::Campaign,
+                        }
+                    }
+                }
+            }
+            RunState::Career => {
+                if gui::career_menu(self, ctx) == gui::CareerResult::Cancel {
+                    newrunstate = RunState::MainMenu {
+                        selection: MainMenuSelection::Career,
+                    }
+                }
+            }
             RunState::AwaitingInput => {
                 newrunstate = player::input(self, ctx);
             }
@@ -210,8 +415,8 @@ impl GameState for State {
             RunState::Examining => {
                 newrunstate = draw_examination_panel(self, ctx);
             }
-            RunState::Notes => {
-                newrunstate = draw_notes(self, ctx);
+            RunState::Notes { page } => {
+                newrunstate = draw_notes(self, ctx, page);
             }
             RunState::Accuse => {
                 newrunstate = draw_accuse_panel(self, ctx);
@@ -249,7 +454,7 @@ impl GameState for State {
                 };
             }
             RunState::GameOver { .. } => {
-                let result = gui::game_over(ctx);
+                let result = gui::game_over(self, ctx);
                 match result {
                     GameOverResult::None => {
                         newrunstate = RunState::GameOver {
@@ -260,7 +465,37 @@ impl GameState for State {
                         newrunstate = RunState::MainMenu {
                             selection: MainMenuSelection::Play,
                         };
-                        self.new_game();
+
+                        let verdict = {
+                            let accusation = self.ecs.fetch::<Accusation>();
+                            if accusation.submitted {
+                                Some(accusation.is_correct(&self.ecs.fetch::<Story>()))
+                            } else {
+                                None
+                            }
+                        };
+
+                        if let Some(correct) = verdict {
+                            if correct {
+                                self.campaign.solve_current();
+                            }
+                            self.profile.record(correct);
+                            self.profile.campaign_solved = self.campaign.solved;
+                            if let Err(e) = self.profile.save(PROFILE_PATH) {
+                                rltk::console::log(format!(
+                                    "Could not save {}: {}",
+                                    PROFILE_PATH, e
+                                ));
+                            }
+                        }
+
+                        if self.campaign.current.is_some() {
+                            newrunstate = RunState::Campaign {
+                                selection: self.campaign.next_chapter(),
+                            };
+                        }
+
+                        self.new_game(None);
                     }
                 }
             }
@@ -279,9 +514,25 @@ fn main() {
     use rltk::RltkBuilder;
     let context = RltkBuilder::simple(SCREEN_WIDTH, SCREEN_HEIGHT).build();
 
-    let mut gs = State { ecs: World::new() };
-
-    gs.new_game();
+    let profile = Profile::load(PROFILE_PATH);
+    let mut gs = State {
+        ecs: World::new(),
+        content: Content::load(),
+        difficulty: Difficulty::Normal,
+        campaign: Campaign::new(profile.campaign_solved),
+        profile,
+    };
+
+    gs.new_game(None);
+
+    // A case file can be opened by passing its path on the command line.
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Some(path) = std::env::args().nth(1) {
+        match CaseFile::load(&path, &gs.content) {
+            Ok(case) => gs.load_case(case),
+            Err(e) => rltk::console::log(format!("Could not open {}: {}", path, e)),
+        }
+    }
 
     rltk::main_loop(context, gs)
 }
@@ -1,22 +1,88 @@
+use std::collections::HashSet;
+
 use rltk::Console;
+use rltk::RandomNumberGenerator;
 use rltk::Rltk;
 use rltk::RGB;
+use rltk::{Algorithm2D, BaseMap, DistanceAlg, Point, Rect};
 use specs::{Builder, WorldExt};
 
 use crate::components::*;
 use crate::constants::*;
+use crate::content::BuildingDef;
 use crate::{story::Story, State};
 
+#[derive(Clone)]
+pub struct Room {
+    pub name: String,
+    pub level: i32,
+    pub rect: Rect,
+}
+
+type Spot = (i32, i32, i32);
+
+/// Floors go from the lowest up. Positions are `(x, y, level)`.
 #[derive(Clone)]
 pub struct Map {
-    tiles: Vec<Tile>,
+    pub levels: Vec<Level>,
+    ground: i32,
+    stairs: Vec<(Spot, Spot)>,
+    pub start: (i32, i32, i32),
 }
 
 impl Map {
     pub fn from_story(story: &Story, gs: &mut State) -> Self {
+        let building = gs.content.building(&story.building).clone();
+
+        let mut occupied = HashSet::new();
+        let (map, victim_pos, suspect_pos, witness_pos, clue_pos) = {
+            let mut rng = gs.ecs.write_resource::<RandomNumberGenerator>();
+            let sealed = story.lock.as_ref().map(|lock| lock.room.as_str());
+            let mut map = Map::gen_layout(&building, &mut rng, sealed);
+
+            map.start = map.spot_in(&building.entrance, &mut rng, &mut occupied);
+
+            // The body stays where it fell, everyone waits wherever they say
+            // they were, and the weapons are wherever they were left. The
+            // murder weapon was put away in the locked room, if there is one.
+            let victim_pos = map.spot_in(&story.victim.crime_scene, &mut rng, &mut occupied);
+            let suspect_pos: Vec<(i32, i32, i32)> = story
+                .suspects
+                .iter()
+                .map(|suspect| map.spot_in(&suspect.alibi, &mut rng, &mut occupied))
+                .collect();
+            let witness_pos: Vec<(i32, i32, i32)> = story
+                .witnesses
+                .iter()
+                .map(|_| map.spot_in(&building.entrance, &mut rng, &mut occupied))
+                .collect();
+            let open_rooms: Vec<String> = map
+                .rooms()
+                .map(|room| room.name.to_string())
+                .filter(|room| Some(room.as_str()) != sealed)
+                .collect();
+            let clue_pos: Vec<(i32, i32, i32)> = story
+                .clues
+                .iter()
+                .map(|clue| {
+                    let room = match sealed {
+                        Some(room) if clue.is_murder_weapon => room.to_string(),
+                        _ => open_rooms[rng.range(0, open_rooms.len())].to_string(),
+                    };
+                    map.spot_in(&room, &mut rng, &mut occupied)
+                })
+                .collect();
+
+            (map, victim_pos, suspect_pos, witness_pos, clue_pos)
+        };
+
         gs.ecs
             .create_entity()
-            .with(Position { x: 2, y: 2 })
+            .with(Position {
+                x: victim_pos.0,
+                y: victim_pos.1,
+                level: victim_pos.2,
+            })
             .with(Renderable {
                 glyph: rltk::to_cp437('V'),
                 fg: story.victim.clue.color,
@@ -25,110 +91,694 @@ impl Map {
             .with(story.victim.clue.clone())
             .build();
 
-        let mut x = 5;
-        for suspect in story.suspects.iter() {
-            let mut rng = rltk::RandomNumberGenerator::new();
-            let cd = rng.range(75, 200);
+        let mut glyphs = GlyphAssigner::new();
+
+        for (suspect, (x, y, level)) in story.suspects.iter().zip(suspect_pos) {
+            let cd = gs
+                .ecs
+                .write_resource::<RandomNumberGenerator>()
+                .range(75, 200);
             gs.ecs
                 .create_entity()
-                .with(Position { x, y: 10 })
+                .with(Position { x, y, level })
                 .with(MovementAI {
                     is_idle: false,
                     cooldown: cd,
                     max_cooldown: cd,
                 })
                 .with(Renderable {
-                    glyph: rltk::to_cp437(suspect.name.chars().nth(0).unwrap()),
+                    glyph: glyphs.assign(&suspect.name, true),
                     fg: RGB::named(suspect.color),
                     bg: rltk::RGB::named(rltk::BLACK),
                 })
                 .with(suspect.clone())
-                .with(ConversationAI {
-                    innocent: !suspect.is_killer,
-                    evidence_hair: suspect.is_killer,
-                    evidence_shoe_size: suspect.is_killer,
-                })
+                .with(ConversationAI {})
                 .build();
+        }
 
-            x += 5;
+        for (witness, (x, y, level)) in story.witnesses.iter().zip(witness_pos) {
+            let cd = gs
+                .ecs
+                .write_resource::<RandomNumberGenerator>()
+                .range(75, 200);
+            gs.ecs
+                .create_entity()
+                .with(Position { x, y, level })
+                .with(MovementAI {
+                    is_idle: false,
+                    cooldown: cd,
+                    max_cooldown: cd,
+                })
+                .with(Renderable {
+                    glyph: glyphs.assign(&witness.name, true),
+                    fg: RGB::named(witness.color),
+                    bg: rltk::RGB::named(rltk::BLACK),
+                })
+                .with(witness.clone())
+                .with(ConversationAI {})
+                .build();
         }
 
-        x = 5;
-        for clue in story.clues.iter() {
+        for (clue, (x, y, level)) in story.clues.iter().zip(clue_pos) {
             gs.ecs
                 .create_entity()
-                .with(Position { x, y: 14 })
+                .with(Position { x, y, level })
                 .with(Renderable {
-                    glyph: rltk::to_cp437(clue.name.chars().nth(0).unwrap()),
+                    glyph: glyphs.assign(&clue.name, false),
                     fg: clue.color,
                     bg: rltk::RGB::named(rltk::BLACK),
                 })
                 .with(clue.clone())
                 .build();
-
-            x += 5;
         }
 
         gs.ecs.insert(story.connections.clone());
 
-        let tiles = vec![Tile::Empty; (MAP_WIDTH * MAP_HEIGHT) as usize];
+        map
+    }
+
+    /// Keeps the `sealed` room locked. It is dug last so it sits at the end of
+    /// the corridors, and layouts where it still cuts the rest of the building
+    /// off are thrown away.
+    fn gen_layout(
+        building: &BuildingDef,
+        rng: &mut RandomNumberGenerator,
+        sealed: Option<&str>,
+    ) -> Self {
+        let floors: Vec<Vec<&String>> = building
+            .floors
+            .iter()
+            .map(|floor| {
+                let mut rooms: Vec<&String> = floor.iter().collect();
+                rooms.sort_by_key(|room| {
+                    (**room != building.entrance, Some(room.as_str()) == sealed)
+                });
+                rooms
+            })
+            .collect();
+        let ground = building.ground_floor() as i32;
+
+        for _ in 0..MAX_ROOM_ATTEMPTS {
+            let levels = floors
+                .iter()
+                .enumerate()
+                .map(|(i, rooms)| Level::dig_layout(building, i as i32, rooms, rng))
+                .collect();
+            let mut map = Map {
+                levels,
+                ground,
+                stairs: vec![],
+                start: (0, 0, ground),
+            };
+            map.place_stairs(rng, sealed);
+
+            let sealed = match sealed {
+                Some(sealed) => sealed,
+                None => return map,
+            };
+
+            let room = map.room(sealed).clone();
+            map.levels[room.level as usize].lock(&room.rect);
+            if map.reaches_all_but(sealed) {
+                return map;
+            }
+        }
+
+        panic!(
+            "Could not lock {} away in the {}",
+            sealed.unwrap_or_default(),
+            building.name
+        );
+    }
+
+    fn place_stairs(&mut self, rng: &mut RandomNumberGenerator, sealed: Option<&str>) {
+        for lower in 1..self.levels.len() {
+            let lower = lower as i32 - 1;
+            let (x1, y1) = self.levels[lower as usize].stairs_spot(rng, sealed);
+            let (x2, y2) = self.levels[lower as usize + 1].stairs_spot(rng, sealed);
+
+            self.levels[lower as usize].set_tile(x1, y1, Tile::UpStairs);
+            self.levels[lower as usize + 1].set_tile(x2, y2, Tile::DownStairs);
+            self.stairs.push(((x1, y1, lower), (x2, y2, lower + 1)));
+        }
+    }
+
+    pub fn stairs_to(&self, x: i32, y: i32, level: i32) -> Option<(i32, i32, i32)> {
+        self.stairs.iter().find_map(|(a, b)| {
+            if *a == (x, y, level) {
+                Some(*b)
+            } else if *b == (x, y, level) {
+                Some(*a)
+            } else {
+                None
+            }
+        })
+    }
+
+    pub fn unlock(&mut self, name: &str) -> bool {
+        let room = self.room(name).clone();
+        self.levels[room.level as usize].unlock(&room.rect)
+    }
+
+    fn reaches_all_but(&self, sealed: &str) -> bool {
+        let entrance = self.levels[self.ground as usize].rooms[0].rect.center();
+        let mut seen = HashSet::new();
+        let mut frontier = vec![(entrance.x, entrance.y, self.ground)];
+        seen.insert((entrance.x, entrance.y, self.ground));
+
+        while let Some((x, y, level)) = frontier.pop() {
+            let steps = [
+                (x - 1, y, level),
+                (x + 1, y, level),
+                (x, y - 1, level),
+                (x, y + 1, level),
+            ];
 
-        let mut map = Map { tiles };
+            for (nx, ny, nl) in steps.iter().copied().chain(self.stairs_to(x, y, level)) {
+                let passable = matches!(
+                    self.get_tile(nx, ny, nl),
+                    Tile::Floor
+                        | Tile::OpenDoor
+                        | Tile::ClosedDoor
+                        | Tile::UpStairs
+                        | Tile::DownStairs
+                );
+                if passable && seen.insert((nx, ny, nl)) {
+                    frontier.push((nx, ny, nl));
+                }
+            }
+        }
+
+        self.rooms().filter(|room| room.name != sealed).all(|room| {
+            let center = room.rect.center();
+            seen.contains(&(center.x, center.y, room.level))
+        })
+    }
+
+    pub fn rooms(&self) -> impl Iterator<Item = &Room> {
+        self.levels.iter().flat_map(|level| level.rooms.iter())
+    }
+
+    pub fn room(&self, name: &str) -> &Room {
+        self.rooms().find(|room| room.name == name).unwrap()
+    }
+
+    pub fn room_at(&self, x: i32, y: i32, level: i32) -> Option<&Room> {
+        self.levels[level as usize]
+            .rooms
+            .iter()
+            .find(|room| room.rect.point_in_rect(Point::new(x, y)))
+    }
+
+    pub fn room_behind(&self, x: i32, y: i32, level: i32) -> Option<&Room> {
+        self.levels[level as usize]
+            .rooms
+            .iter()
+            .find(|room| Level::ring(&room.rect).contains(&(x, y)))
+    }
 
-        for x in 0..MAP_WIDTH / 2 {
-            map.set_tile(x, 0, Tile::Wall);
-            map.set_tile(x, MAP_HEIGHT / 2 - 1, Tile::Wall);
+    pub fn level_name(&self, level: i32) -> String {
+        match level - self.ground {
+            0 => "the ground floor".to_string(),
+            n if n < 0 => "the basement".to_string(),
+            n => format!("floor {}", n),
         }
+    }
+
+    pub fn spot_in(
+        &self,
+        name: &str,
+        rng: &mut RandomNumberGenerator,
+        occupied: &mut HashSet<(i32, i32, i32)>,
+    ) -> (i32, i32, i32) {
+        let room = self.room(name);
+        let rect = &room.rect;
 
-        for y in 0..MAP_HEIGHT / 2 {
-            map.set_tile(0, y, Tile::Wall);
-            map.set_tile(MAP_WIDTH / 2 - 1, y, Tile::Wall);
+        loop {
+            let spot = (
+                rng.range(rect.x1, rect.x2 + 1),
+                rng.range(rect.y1, rect.y2 + 1),
+                room.level,
+            );
+            if self.get_tile(spot.0, spot.1, spot.2) == Tile::Floor && occupied.insert(spot) {
+                return spot;
+            }
         }
+    }
 
-        for y in 1..MAP_HEIGHT / 2 - 1 {
-            for x in 1..MAP_WIDTH / 2 - 1 {
-                map.set_tile(x, y, Tile::Floor);
+    /// Nothing on the other floors is in view.
+    pub fn update_view(&mut self, x: i32, y: i32, level: i32, daylight: f32) {
+        for (i, floor) in self.levels.iter_mut().enumerate() {
+            if i as i32 == level {
+                floor.update_view(x, y, daylight);
+            } else {
+                floor.hide();
+            }
+        }
+    }
+
+    pub fn is_visible(&self, x: i32, y: i32, level: i32) -> bool {
+        self.levels[level as usize].visible[(y * MAP_WIDTH + x) as usize]
+    }
+
+    pub fn draw(&self, ctx: &mut Rltk, player_x: i32, player_y: i32, level: i32) {
+        self.levels[level as usize].draw(ctx, player_x, player_y);
+    }
+
+    pub fn get_tile(&self, x: i32, y: i32, level: i32) -> Tile {
+        self.levels[level as usize].get_tile(x, y)
+    }
+
+    pub fn set_tile(&mut self, x: i32, y: i32, level: i32, tile: Tile) {
+        self.levels[level as usize].set_tile(x, y, tile);
+    }
+}
+
+#[derive(Clone)]
+pub struct Level {
+    tiles: Vec<Tile>,
+    visible: Vec<bool>,
+    revealed: Vec<bool>,
+    lit: Vec<bool>,
+    daylight: f32,
+    pub rooms: Vec<Room>,
+}
+
+impl Level {
+    fn dig_layout(
+        building: &BuildingDef,
+        level: i32,
+        names: &[&String],
+        rng: &mut RandomNumberGenerator,
+    ) -> Self {
+        let tiles = vec![Tile::Empty; (MAP_WIDTH * MAP_HEIGHT) as usize];
+
+        let mut map = Level {
+            tiles,
+            visible: vec![false; (MAP_WIDTH * MAP_HEIGHT) as usize],
+            revealed: vec![false; (MAP_WIDTH * MAP_HEIGHT) as usize],
+            lit: vec![false; (MAP_WIDTH * MAP_HEIGHT) as usize],
+            daylight: 1.0,
+            rooms: vec![],
+        };
+
+        for name in names {
+            let mut placed = false;
+
+            for _ in 0..MAX_ROOM_ATTEMPTS {
+                let w = rng.range(building.room_width.0, building.room_width.1 + 1);
+                let h = rng.range(building.room_height.0, building.room_height.1 + 1);
+                let x = rng.range(2, MAP_WIDTH - w - 2);
+                let y = rng.range(2, MAP_HEIGHT - h - 2);
+
+                // Leave room for walls and a corridor between neighbours.
+                let rect = Rect::with_size(x, y, w, h);
+                let padded = Rect::with_size(x - 3, y - 3, w + 6, h + 6);
+                if map.rooms.iter().any(|room| room.rect.intersect(&padded)) {
+                    continue;
+                }
+
+                if let Some(previous) = map.rooms.last() {
+                    let from = previous.rect.center();
+                    let to = rect.center();
+                    map.dig_corridor(rng, (from.x, from.y), (to.x, to.y), building.corridor_width);
+                }
+
+                rect.for_each(|point| map.set_tile(point.x, point.y, Tile::Floor));
+                map.rooms.push(Room {
+                    name: name.to_string(),
+                    level,
+                    rect,
+                });
+
+                placed = true;
+                break;
+            }
+
+            if !placed {
+                panic!("Could not fit {} into the {}", name, building.name);
+            }
+        }
+
+        for y in 0..MAP_HEIGHT {
+            for x in 0..MAP_WIDTH {
+                if map.get_tile(x, y) == Tile::Empty && map.borders_floor(x, y) {
+                    map.set_tile(x, y, Tile::Wall);
+                }
             }
         }
 
+        map.hang_doors();
+        map.hang_lamps(rng);
+
         map
     }
 
-    pub fn draw(&self, ctx: &mut Rltk, player_x: i32, player_y: i32) {
+    /// Puts a door on every way into a room, except where a corridor merely
+    /// runs along its side.
+    fn hang_doors(&mut self) {
+        let mut doors = vec![];
+
+        for room in self.rooms.iter() {
+            let rect = &room.rect;
+
+            for (x, y) in Level::ring(rect) {
+                if self.get_tile(x, y) != Tile::Floor {
+                    continue;
+                }
+
+                let along = if y == rect.y1 - 1 || y == rect.y2 + 1 {
+                    [(x - 1, y), (x + 1, y)]
+                } else {
+                    [(x, y - 1), (x, y + 1)]
+                };
+                if along
+                    .iter()
+                    .all(|(x, y)| self.get_tile(*x, *y) == Tile::Floor)
+                {
+                    continue;
+                }
+
+                doors.push((x, y));
+            }
+        }
+
+        for (x, y) in doors {
+            self.set_tile(x, y, Tile::ClosedDoor);
+        }
+    }
+
+    /// The first room on each floor is always lit.
+    fn hang_lamps(&mut self, rng: &mut RandomNumberGenerator) {
+        for i in 0..self.rooms.len() {
+            if i > 0 && rng.range(0, 100) >= LAMP_CHANCE {
+                continue;
+            }
+
+            let rect = self.rooms[i].rect;
+            let walls: Vec<(i32, i32)> = Level::ring(&rect)
+                .into_iter()
+                .filter(|(x, y)| {
+                    let corner = (*x == rect.x1 - 1 || *x == rect.x2 + 1)
+                        && (*y == rect.y1 - 1 || *y == rect.y2 + 1);
+                    !corner && self.get_tile(*x, *y) == Tile::Wall
+                })
+                .collect();
+            if walls.is_empty() {
+                continue;
+            }
+
+            let (x, y) = walls[rng.range(0, walls.len())];
+            self.set_tile(x, y, Tile::Lamp);
+
+            for (x, y) in Level::ring(&rect) {
+                self.lit[(y * MAP_WIDTH + x) as usize] = true;
+            }
+            rect.for_each(|point| self.lit[(point.y * MAP_WIDTH + point.x) as usize] = true);
+        }
+    }
+
+    fn stairs_spot(&self, rng: &mut RandomNumberGenerator, sealed: Option<&str>) -> (i32, i32) {
+        let rooms: Vec<&Room> = self
+            .rooms
+            .iter()
+            .filter(|room| Some(room.name.as_str()) != sealed)
+            .collect();
+        let rect = &rooms[rng.range(0, rooms.len())].rect;
+
+        loop {
+            let x = rng.range(rect.x1, rect.x2 + 1);
+            let y = rng.range(rect.y1, rect.y2 + 1);
+            if self.get_tile(x, y) == Tile::Floor {
+                return (x, y);
+            }
+        }
+    }
+
+    fn ring(rect: &Rect) -> Vec<(i32, i32)> {
+        let mut ring = vec![];
+
+        for x in rect.x1 - 1..=rect.x2 + 1 {
+            ring.push((x, rect.y1 - 1));
+            ring.push((x, rect.y2 + 1));
+        }
+        for y in rect.y1..=rect.y2 {
+            ring.push((rect.x1 - 1, y));
+            ring.push((rect.x2 + 1, y));
+        }
+
+        ring
+    }
+
+    /// Locks every way into a room, even where a corridor only runs past it,
+    /// so that nobody gets in without the key.
+    fn lock(&mut self, rect: &Rect) {
+        for (x, y) in Level::ring(rect) {
+            match self.get_tile(x, y) {
+                Tile::Wall | Tile::Empty => {}
+                _ => self.set_tile(x, y, Tile::LockedDoor),
+            }
+        }
+    }
+
+    fn unlock(&mut self, rect: &Rect) -> bool {
+        let mut unlocked = false;
+
+        for (x, y) in Level::ring(rect) {
+            if self.get_tile(x, y) == Tile::LockedDoor {
+                self.set_tile(x, y, Tile::ClosedDoor);
+                unlocked = true;
+            }
+        }
+
+        unlocked
+    }
+
+    fn dig_corridor(
+        &mut self,
+        rng: &mut RandomNumberGenerator,
+        from: (i32, i32),
+        to: (i32, i32),
+        width: i32,
+    ) {
+        let corner = if rng.range(0, 2) == 0 {
+            (to.0, from.1)
+        } else {
+            (from.0, to.1)
+        };
+
+        for (a, b) in [(from, corner), (corner, to)].iter() {
+            for x in a.0.min(b.0)..=a.0.max(b.0) {
+                for y in a.1.min(b.1)..=a.1.max(b.1) {
+                    for d in 0..width {
+                        if a.1 == b.1 {
+                            self.set_tile(x, y + d, Tile::Floor);
+                        } else {
+                            self.set_tile(x + d, y, Tile::Floor);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn borders_floor(&self, x: i32, y: i32) -> bool {
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                let (nx, ny) = (x + dx, y + dy);
+                if (0..MAP_WIDTH).contains(&nx)
+                    && (0..MAP_HEIGHT).contains(&ny)
+                    && self.get_tile(nx, ny) == Tile::Floor
+                {
+                    return true;
+                }
+            }
+        }
+
+        false
+    }
+
+    /// Anything seen once stays on the map, but only what is in view is up to
+    /// date. In the dark only what is close by or lit by a lamp can be made out.
+    fn update_view(&mut self, x: i32, y: i32, daylight: f32) {
+        self.daylight = daylight;
+        self.hide();
+
+        let start = Point::new(x, y);
+        let radius = NIGHT_VIEW_RADIUS as f32 + (VIEW_RADIUS - NIGHT_VIEW_RADIUS) as f32 * daylight;
+
+        for point in rltk::field_of_view(start, VIEW_RADIUS, self) {
+            let idx = self.point2d_to_index(point);
+            if self.lit[idx] || DistanceAlg::Pythagoras.distance2d(start, point) <= radius {
+                self.visible[idx] = true;
+                self.revealed[idx] = true;
+            }
+        }
+    }
+
+    fn hide(&mut self) {
+        for visible in self.visible.iter_mut() {
+            *visible = false;
+        }
+    }
+
+    fn draw(&self, ctx: &mut Rltk, player_x: i32, player_y: i32) {
         for y in 0..MAP_HEIGHT {
             for x in 0..MAP_WIDTH {
                 let screen_x = MAP_WIDTH / 2 + 1 + x - player_x;
                 let screen_y = MAP_HEIGHT / 2 + 1 + y - player_y;
+                let idx = (y * MAP_WIDTH + x) as usize;
+
+                if !self.revealed[idx] {
+                    continue;
+                }
 
                 if (0..MAP_WIDTH).contains(&screen_x) && (0..MAP_HEIGHT).contains(&screen_y) {
+                    let (glyph, fg) = match self.get_tile(x, y) {
+                        Tile::Floor => ('.', rltk::GRAY),
+                        Tile::Wall => ('#', rltk::GRAY),
+                        Tile::Empty => (' ', rltk::GRAY),
+                        Tile::OpenDoor => ('/', rltk::CHOCOLATE),
+                        Tile::ClosedDoor => ('+', rltk::CHOCOLATE),
+                        Tile::LockedDoor => ('+', rltk::RED),
+                        Tile::Lamp => ('*', rltk::GOLD),
+                        Tile::UpStairs => ('<', rltk::WHITE),
+                        Tile::DownStairs => ('>', rltk::WHITE),
+                    };
+                    let fg = if self.visible[idx] && self.lit[idx] {
+                        RGB::named(fg)
+                    } else if self.visible[idx] {
+                        RGB::named(fg) * (0.5 + 0.5 * self.daylight)
+                    } else {
+                        RGB::named(fg).to_greyscale() * 0.4
+                    };
+
                     ctx.set(
                         screen_x,
                         screen_y,
-                        RGB::named(rltk::GRAY),
+                        fg,
                         RGB::named(rltk::BLACK),
-                        match self.get_tile(x, y) {
-                            Tile::Floor => rltk::to_cp437('.'),
-                            Tile::Wall => rltk::to_cp437('#'),
-                            Tile::Empty => rltk::to_cp437(' '),
-                        },
+                        rltk::to_cp437(glyph),
                     );
                 }
             }
         }
     }
 
-    pub fn get_tile(&self, x: i32, y: i32) -> Tile {
+    fn get_tile(&self, x: i32, y: i32) -> Tile {
         self.tiles[(y * MAP_WIDTH + x) as usize].clone()
     }
 
-    pub fn set_tile(&mut self, x: i32, y: i32, tile: Tile) {
+    fn set_tile(&mut self, x: i32, y: i32, tile: Tile) {
         self.tiles[(y * MAP_WIDTH + x) as usize] = tile;
     }
 }
 
+impl BaseMap for Level {
+    fn is_opaque(&self, idx: usize) -> bool {
+        !matches!(
+            self.tiles[idx],
+            Tile::Floor | Tile::OpenDoor | Tile::UpStairs | Tile::DownStairs
+        )
+    }
+}
+
+impl Algorithm2D for Level {
+    fn dimensions(&self) -> Point {
+        Point::new(MAP_WIDTH, MAP_HEIGHT)
+    }
+}
+
+/// Hands out a distinct glyph per entity, preferring the initials of its
+/// name. Suspects are drawn in upper case and clues in lower case.
+struct GlyphAssigner {
+    used: HashSet<char>,
+}
+
+impl GlyphAssigner {
+    fn new() -> Self {
+        let mut used = HashSet::new();
+        used.insert('@');
+        used.insert('V');
+
+        GlyphAssigner { used }
+    }
+
+    fn assign(&mut self, name: &str, upper: bool) -> u8 {
+        let initials = name
+            .split_whitespace()
+            .filter_map(|word| word.chars().next());
+        let letters = name.chars();
+        let alphabet = 'a'..='z';
+
+        for c in initials.chain(letters).chain(alphabet) {
+            if !c.is_ascii_alphabetic() {
+                continue;
+            }
+
+            let c = if upper {
+                c.to_ascii_uppercase()
+            } else {
+                c.to_ascii_lowercase()
+            };
+
+            if self.used.insert(c) {
+                return rltk::to_cp437(c);
+            }
+        }
+
+        rltk::to_cp437('?')
+    }
+}
+
 #[derive(Clone, PartialEq, Eq)]
 pub enum Tile {
     Wall,
     Floor,
     Empty,
+    OpenDoor,
+    ClosedDoor,
+    LockedDoor,
+    Lamp,
+    UpStairs,
+    DownStairs,
+}
+
+impl Tile {
+    pub fn is_walkable(&self) -> bool {
+        matches!(
+            self,
+            Tile::Floor | Tile::OpenDoor | Tile::UpStairs | Tile::DownStairs
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content::Content;
+
+    #[test]
+    fn locked_rooms_are_sealed_off_from_the_rest() {
+        let content = Content::load();
+
+        for building in content.buildings.iter() {
+            let rooms: Vec<&String> = building
+                .rooms
+                .iter()
+                .filter(|room| building.shares_floor(room))
+                .collect();
+
+            for seed in 0..5 {
+                let mut rng = RandomNumberGenerator::seeded(seed);
+                let sealed = rooms[seed as usize % rooms.len()];
+                let mut map = Map::gen_layout(building, &mut rng, Some(sealed));
+
+                assert!(map.reaches_all_but(sealed), "{} #{}", building.name, seed);
+                assert!(!map.reaches_all_but(""), "{} #{}", building.name, seed);
+                assert!(map.unlock(sealed));
+                assert!(map.reaches_all_but(""), "{} #{}", building.name, seed);
+            }
+        }
+    }
 }
@@ -0,0 +1,87 @@
+use crate::content::{fill_template, Content};
+use crate::story::{format_hour, ConnectionType, Story, Strike};
+
+fn fields(story: &Story, content: &Content) -> Vec<(&'static str, String)> {
+    let victim = &story.victim;
+    let (died_from, wound) = &content.weapon(&victim.weapon_used).cause_of_death;
+
+    let mut fields = vec![
+        ("victim", victim.name.to_string()),
+        ("age", victim.age.to_string()),
+        ("building", story.building.to_string()),
+        ("location", victim.crime_scene.to_string()),
+        ("cause", format!("{} {}", died_from, wound)),
+        ("time", format_hour(victim.time_of_death)),
+        ("count", story.suspects.len().to_string()),
+        ("weapon", victim.weapon_used.to_string()),
+    ];
+
+    if let Some(killer) = story.suspects.iter().find(|s| s.is_killer) {
+        fields.push(("killer", killer.name.to_string()));
+        fields.push(("motive", killer.motive.to_string()));
+        fields.push(("relationship", killer.relationship.to_string()));
+        fields.push(("alibi", killer.alibi.to_string()));
+    }
+
+    if let Some(accomplice) = story.suspects.iter().find(|s| s.is_accomplice) {
+        fields.push(("accomplice", accomplice.name.to_string()));
+    }
+
+    fields
+}
+
+fn fill_lines(lines: &[String], fields: &[(&str, String)]) -> Vec<String> {
+    let fields: Vec<(&str, &str)> = fields.iter().map(|(k, v)| (*k, v.as_str())).collect();
+
+    lines
+        .iter()
+        .map(|line| fill_template(line, &fields))
+        .collect()
+}
+
+/// The lines the player is handed when they take the case.
+pub fn briefing(story: &Story, content: &Content) -> Vec<String> {
+    fill_lines(&content.narrative.briefing, &fields(story, content))
+}
+
+/// The news of a later murder in a serial case.
+pub fn strike(strike: &Strike, content: &Content) -> String {
+    fill_template(
+        &content.narrative.strike,
+        &[
+            ("victim", strike.victim.as_str()),
+            ("location", strike.crime_scene.as_str()),
+        ],
+    )
+}
+
+/// What really happened, told from the ground truth once the case is over,
+/// followed by the deductions that proved it.
+pub fn epilogue(story: &Story, content: &Content) -> Vec<String> {
+    let fields = fields(story, content);
+    let narrative = &content.narrative;
+
+    let mut lines = fill_lines(&narrative.epilogue, &fields);
+
+    if story.suspects.iter().any(|s| s.is_accomplice) {
+        lines.extend(fill_lines(
+            std::slice::from_ref(&narrative.accomplice),
+            &fields,
+        ));
+    }
+
+    lines.push(String::new());
+    lines.push(narrative.proof.to_string());
+
+    // Later murders may never have happened, so they prove nothing here.
+    for cxn in story
+        .connections
+        .iter()
+        .filter(|cxn| !matches!(cxn.cxn_type, ConnectionType::Pattern(_)))
+    {
+        let deduction = cxn.note.get_log_msg();
+        lines.push(deduction.trim_start_matches("Aha!").trim().to_string());
+    }
+
+    lines
+}
@@ -1,33 +1,82 @@
+#[cfg(not(target_arch = "wasm32"))]
+use crate::case_file::CaseFile;
 use crate::components::*;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::config::CaseConfig;
+use crate::constants::*;
 use crate::gui::Options;
 use crate::gui::PauseMenuSelection;
+use crate::gui::{Log, Time};
 use crate::map::Map;
 use crate::map::Tile;
 use crate::story::Story;
 use crate::RunState;
 use crate::State;
-use rltk::{Rltk, VirtualKeyCode};
+use rltk::{Rltk, VirtualKeyCode, RGB};
 use specs::prelude::*;
 
 pub fn try_move_player(delta_x: i32, delta_y: i32, ecs: &mut World) {
     let mut pos = *ecs.fetch::<PlayerPosition>();
 
-    let map = ecs.fetch::<Map>();
+    let mut map = ecs.write_resource::<Map>();
 
     let positions = ecs.read_storage::<Position>();
 
     let mut blocked = false;
 
     for position in (&positions).join() {
-        if position.x == pos.x + delta_x && position.y == pos.y + delta_y {
+        if position.level == pos.level
+            && position.x == pos.x + delta_x
+            && position.y == pos.y + delta_y
+        {
             blocked = true;
             break;
         }
     }
 
-    if !blocked && map.get_tile(pos.x + delta_x, pos.y + delta_y) == Tile::Floor {
-        pos.x = pos.x + delta_x;
-        pos.y = pos.y + delta_y;
+    let (x, y) = (pos.x + delta_x, pos.y + delta_y);
+
+    // Closed doors take a step to open, locked ones need the key.
+    match map.get_tile(x, y, pos.level) {
+        Tile::ClosedDoor if !blocked => map.set_tile(x, y, pos.level, Tile::OpenDoor),
+        Tile::LockedDoor => {
+            let room = map
+                .room_behind(x, y, pos.level)
+                .map_or("room", |room| &room.name);
+            ecs.write_resource::<Log>().log_message(
+                &ecs.fetch::<Time>(),
+                "Game",
+                &format!("The door to {} is locked.", room),
+                RGB::named(rltk::WHITE),
+            );
+        }
+        tile if !blocked && tile.is_walkable() => {
+            pos.x = x;
+            pos.y = y;
+
+            // Every step takes a minute, so the day wears on as you walk.
+            ecs.write_resource::<Time>().advance_minute();
+
+            // Stepping onto the stairs takes you to the other end, unless
+            // someone is standing there.
+            if let Some((x, y, level)) = map.stairs_to(x, y, pos.level) {
+                let landing_free = !(&positions)
+                    .join()
+                    .any(|p| p.x == x && p.y == y && p.level == level);
+                if landing_free {
+                    pos.x = x;
+                    pos.y = y;
+                    pos.level = level;
+                    ecs.write_resource::<Log>().log_message(
+                        &ecs.fetch::<Time>(),
+                        "Game",
+                        &format!("You take the stairs to {}.", map.level_name(level)),
+                        RGB::named(rltk::WHITE),
+                    );
+                }
+            }
+        }
+        _ => {}
     }
 
     let mut new_pos = ecs.write_resource::<PlayerPosition>();
@@ -43,44 +92,47 @@ pub fn input(gs: &mut State, ctx: &mut Rltk) -> RunState {
             VirtualKeyCode::Right => try_move_player(1, 0, &mut gs.ecs),
             VirtualKeyCode::Up => try_move_player(0, -1, &mut gs.ecs),
             VirtualKeyCode::Down => try_move_player(0, 1, &mut gs.ecs),
-            VirtualKeyCode::A => {
-                if gs.ecs.fetch::<Options>().options.contains_key(&'A') {
-                    return RunState::Accuse;
-                }
-            }
-            VirtualKeyCode::N => {
-                if gs.ecs.fetch::<Options>().options.contains_key(&'N') {
-                    return RunState::Notes;
-                }
+            VirtualKeyCode::A if gs.ecs.fetch::<Options>().options.contains_key(&'A') => {
+                return RunState::Accuse;
             }
-            VirtualKeyCode::P => {
-                if gs.ecs.fetch::<Options>().options.contains_key(&'P') {
-                    return RunState::Paused {
-                        selection: PauseMenuSelection::Return,
-                    };
-                }
+            VirtualKeyCode::N if gs.ecs.fetch::<Options>().options.contains_key(&'N') => {
+                return RunState::Notes { page: 0 };
             }
-            VirtualKeyCode::L => {
-                if gs.ecs.fetch::<Options>().options.contains_key(&'L') {
-                    return RunState::Log { page: 0 };
-                }
+            VirtualKeyCode::P if gs.ecs.fetch::<Options>().options.contains_key(&'P') => {
+                return RunState::Paused {
+                    selection: PauseMenuSelection::Return,
+                };
             }
-            VirtualKeyCode::T => {
-                if gs.ecs.fetch::<Options>().options.contains_key(&'T') {
-                    return RunState::Talking;
-                }
+            VirtualKeyCode::L if gs.ecs.fetch::<Options>().options.contains_key(&'L') => {
+                return RunState::Log { page: 0 };
             }
-            VirtualKeyCode::X => {
-                if gs.ecs.fetch::<Options>().options.contains_key(&'X') {
-                    return RunState::Examining;
-                }
+            VirtualKeyCode::T if gs.ecs.fetch::<Options>().options.contains_key(&'T') => {
+                return RunState::Talking;
             }
-            VirtualKeyCode::Tab => {
-                let story = gs.ecs.fetch::<Story>();
-                rltk::console::log(&format!("{:?}", *story));
+            VirtualKeyCode::X if gs.ecs.fetch::<Options>().options.contains_key(&'X') => {
+                return RunState::Examining;
             }
+            #[cfg(not(target_arch = "wasm32"))]
+            VirtualKeyCode::Tab => export_case(gs),
             _ => {}
         },
     }
     RunState::AwaitingInput
 }
+
+/// Writes the current case out to the cases directory. The web build has
+/// nowhere to save to or load from, so it doesn't offer this.
+#[cfg(not(target_arch = "wasm32"))]
+fn export_case(gs: &mut State) {
+    let difficulty = gs.ecs.fetch::<CaseConfig>().difficulty;
+    let case = CaseFile::new(&gs.ecs.fetch::<Story>(), difficulty);
+
+    let msg = match case.save(CASES_DIR) {
+        Ok(path) => format!("Case file saved to {}.", path),
+        Err(e) => format!("Could not save the case file: {}", e),
+    };
+
+    let time = gs.ecs.fetch::<Time>();
+    let mut log = gs.ecs.write_resource::<Log>();
+    log.log_message(&time, "Game", &msg, RGB::named(rltk::WHITE));
+}
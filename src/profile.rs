@@ -0,0 +1,209 @@
+use serde::{Deserialize, Serialize};
+
+use crate::config::CaseConfig;
+
+/// Perks the detective picks up as they climb the ranks. They only change how
+/// a case plays, never how it is generated, so case numbers stay shareable.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum Tool {
+    CoronersFavor,
+    Informant,
+}
+
+impl Tool {
+    pub fn name(self) -> &'static str {
+        match self {
+            Tool::CoronersFavor => "Coroner's Favor",
+            Tool::Informant => "Informant",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Tool::CoronersFavor => "The coroner's report arrives in half the time.",
+            Tool::Informant => "You need one less deduction before you can accuse.",
+        }
+    }
+
+    fn apply(self, config: &mut CaseConfig) {
+        match self {
+            Tool::CoronersFavor => config.coroner_delay /= 2,
+            Tool::Informant => config.required_evidence = config.required_evidence.max(2) - 1,
+        }
+    }
+}
+
+pub struct Rank {
+    pub title: &'static str,
+    pub cases_closed: u32,
+    pub unlocks: Option<Tool>,
+}
+
+pub const RANKS: [Rank; 5] = [
+    Rank {
+        title: "Rookie",
+        cases_closed: 0,
+        unlocks: None,
+    },
+    Rank {
+        title: "Detective",
+        cases_closed: 2,
+        unlocks: Some(Tool::CoronersFavor),
+    },
+    Rank {
+        title: "Sergeant",
+        cases_closed: 5,
+        unlocks: Some(Tool::Informant),
+    },
+    Rank {
+        title: "Inspector",
+        cases_closed: 10,
+        unlocks: None,
+    },
+    Rank {
+        title: "Chief Inspector",
+        cases_closed: 20,
+        unlocks: None,
+    },
+];
+
+/// The detective's career, kept between cases and between sessions.
+#[derive(Serialize, Deserialize, Default)]
+pub struct Profile {
+    #[serde(default)]
+    pub cases_closed: u32,
+    #[serde(default)]
+    pub cases_failed: u32,
+    #[serde(default)]
+    pub campaign_solved: usize,
+}
+
+impl Profile {
+    pub fn rank(&self) -> &'static Rank {
+        RANKS
+            .iter()
+            .rev()
+            .find(|rank| self.cases_closed >= rank.cases_closed)
+            .unwrap_or(&RANKS[0])
+    }
+
+    /// The rank after this one, if there is one left to earn.
+    pub fn next_rank(&self) -> Option<&'static Rank> {
+        RANKS
+            .iter()
+            .find(|rank| self.cases_closed < rank.cases_closed)
+    }
+
+    /// Percentage of accusations that were right, once there has been one.
+    pub fn accuracy(&self) -> Option<u32> {
+        let total = self.cases_closed + self.cases_failed;
+        (self.cases_closed * 100).checked_div(total)
+    }
+
+    pub fn tools(&self) -> Vec<Tool> {
+        RANKS
+            .iter()
+            .filter(|rank| self.cases_closed >= rank.cases_closed)
+            .filter_map(|rank| rank.unlocks)
+            .collect()
+    }
+
+    pub fn apply_tools(&self, config: &mut CaseConfig) {
+        for tool in self.tools() {
+            tool.apply(config);
+        }
+    }
+
+    pub fn record(&mut self, correct: bool) {
+        if correct {
+            self.cases_closed += 1;
+        } else {
+            self.cases_failed += 1;
+        }
+    }
+
+    /// Reads the profile from disk, starting a fresh career if there isn't one
+    /// or it can't be read.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load(path: &str) -> Self {
+        match std::fs::read_to_string(path) {
+            Ok(ron) => ron::de::from_str(&ron).unwrap_or_else(|e| {
+                rltk::console::log(format!("Could not read {}: {}", path, e));
+                Profile::default()
+            }),
+            Err(_) => Profile::default(),
+        }
+    }
+
+    /// The browser has no files, so there the profile lives in local storage
+    /// under the same name.
+    #[cfg(target_arch = "wasm32")]
+    pub fn load(path: &str) -> Self {
+        match local_storage().and_then(|storage| storage.get_item(path).ok()?) {
+            Some(ron) => ron::de::from_str(&ron).unwrap_or_else(|e| {
+                rltk::console::log(format!("Could not read {}: {}", path, e));
+                Profile::default()
+            }),
+            None => Profile::default(),
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        let ron = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::new())
+            .map_err(|e| e.to_string())?;
+        std::fs::write(path, ron).map_err(|e| e.to_string())
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        let ron = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::new())
+            .map_err(|e| e.to_string())?;
+        local_storage()
+            .ok_or_else(|| "local storage is not available".to_string())?
+            .set_item(path, &ron)
+            .map_err(|_| format!("Could not write {} to local storage", path))
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Difficulty;
+
+    fn profile(cases_closed: u32) -> Profile {
+        Profile {
+            cases_closed,
+            ..Profile::default()
+        }
+    }
+
+    #[test]
+    fn ranks_follow_cases_closed() {
+        assert_eq!(profile(0).rank().title, "Rookie");
+        assert_eq!(profile(4).rank().title, "Detective");
+        assert_eq!(profile(5).rank().title, "Sergeant");
+        assert_eq!(profile(20).next_rank().map(|rank| rank.title), None);
+    }
+
+    #[test]
+    fn tools_unlock_with_rank() {
+        assert_eq!(profile(1).tools(), vec![]);
+        assert_eq!(profile(2).tools(), vec![Tool::CoronersFavor]);
+        assert_eq!(
+            profile(12).tools(),
+            vec![Tool::CoronersFavor, Tool::Informant]
+        );
+
+        let mut config = CaseConfig::new(Difficulty::Normal);
+        let (delay, required) = (config.coroner_delay, config.required_evidence);
+        profile(5).apply_tools(&mut config);
+        assert_eq!(config.coroner_delay, delay / 2);
+        assert_eq!(config.required_evidence, required - 1);
+    }
+}
@@ -1,171 +1,299 @@
 use std::collections::HashSet;
 
 use rltk::{RandomNumberGenerator, RGB};
+use serde::{Deserialize, Serialize};
 use specs::prelude::*;
 use specs_derive::Component;
 
+use crate::config::CaseConfig;
 use crate::constants::*;
+use crate::content::{
+    fill_template, BuildingDef, Content, RelationshipDef, TraceSource, TraitDef, WeaponDef,
+};
+use crate::verifier::verify;
 
-#[derive(Debug)]
+pub const WHEREABOUTS: &str = "whereabouts";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Story {
+    pub seed: u64,
+    #[serde(default = "Story::default_building")]
+    pub building: String,
     pub victim: Victim,
     pub suspects: Vec<Suspect>,
+    pub witnesses: Vec<Witness>,
     pub clues: Vec<Clue>,
     pub connections: Vec<Connection>,
+    #[serde(default)]
+    pub strikes: Vec<Strike>,
+    #[serde(default)]
+    pub lock: Option<Lock>,
 }
 
 impl Story {
-    pub fn gen_rand() -> Self {
-        let victim = Victim::gen_rand();
-        let suspects = Suspect::gen_rand_suspects(&victim);
-        let clues = Clue::gen_rand_clues(&victim);
-        let connections = Connection::gen_connections(&victim, suspects.clone(), clues.clone());
+    pub fn gen_rand(
+        seed: u64,
+        rng: &mut RandomNumberGenerator,
+        content: &Content,
+        config: &CaseConfig,
+    ) -> Self {
+        for _ in 0..MAX_GENERATION_ATTEMPTS {
+            let story = Story::gen_candidate(seed, rng, content, config);
+
+            match verify(&story) {
+                Ok(()) => return story,
+                Err(reason) => {
+                    rltk::console::log(format!("Rejected case #{}: {}", seed, reason));
+                }
+            }
+        }
+
+        panic!(
+            "Could not generate a solvable case #{} in {} attempts",
+            seed, MAX_GENERATION_ATTEMPTS
+        );
+    }
+
+    /// Cases saved before there was more than one kind of building.
+    fn default_building() -> String {
+        "mansion".to_string()
+    }
+
+    fn gen_candidate(
+        seed: u64,
+        rng: &mut RandomNumberGenerator,
+        content: &Content,
+        config: &CaseConfig,
+    ) -> Self {
+        let buildings = &content.buildings;
+        let building = &buildings[rng.range(0, buildings.len())];
+
+        let mut names = NameGenerator::new();
+        let mut victim = Victim::gen_rand(rng, content, config, &mut names, building);
+        let mut suspects =
+            Suspect::gen_rand_suspects(rng, content, config, &mut names, &victim, building);
+        let witnesses = Witness::gen_rand_witnesses(rng, content, config, &mut names, &victim);
+        let clues = Clue::gen_rand_clues(rng, content, config, &victim, &suspects);
+        Suspect::gen_testimony(content, &mut suspects, &clues);
+        let strikes = Strike::gen_rand_strikes(
+            rng, content, config, &mut names, &victim, &suspects, building,
+        );
+        let lock = Lock::gen_rand(
+            rng,
+            content,
+            config,
+            building,
+            &mut victim,
+            &mut suspects,
+            &strikes,
+        );
+        let connections = Connection::gen_connections(
+            &victim,
+            suspects.clone(),
+            &witnesses,
+            clues.clone(),
+            &strikes,
+        );
 
         Story {
+            seed,
+            building: building.name.to_string(),
             victim,
             suspects,
+            witnesses,
             clues,
             connections,
+            strikes,
+            lock,
         }
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Victim {
     pub name: String,
     pub age: i32,
     pub weapon_used: String,
-    pub hair_found: String,
-    pub shoe_print: String,
+    pub evidence: Vec<(String, String)>,
+    pub accomplice_evidence: Vec<String>,
+    pub relationship_found: String,
+    pub time_of_death: i32,
+    pub crime_scene: String,
     pub clue: Clue,
+    pub report: Vec<Note>,
+    pub forensic_trace: String,
 }
 
 impl Victim {
-    fn gen_rand() -> Self {
-        let mut rand = RandomNumberGenerator::new();
+    fn gen_rand(
+        rand: &mut RandomNumberGenerator,
+        content: &Content,
+        config: &CaseConfig,
+        names: &mut NameGenerator,
+        building: &BuildingDef,
+    ) -> Self {
+        let name = names.gen_name(rand, content);
+
+        let age = rand.range(content.ages.0, content.ages.1);
+
+        let weapons = &content.weapons;
+        let weapon_used = weapons[rand.range(0, weapons.len())].name.to_string();
+
+        // Only one trait per case is left on the weapon, since the smaller
+        // weapons don't have room for many markers.
+        let mut traits: Vec<&TraitDef> = content.evidence_traits.iter().collect();
+        let mut evidence = vec![];
+        for _ in 0..config.evidence_traits {
+            let evidence_trait = traits.remove(rand.range(0, traits.len()));
+            if evidence_trait.found_on == TraceSource::Weapon {
+                traits.retain(|t| t.found_on != TraceSource::Weapon);
+            }
 
-        let names = ["Hercule", "Sherlock", "Johnny"];
-        let name = names[rand.range(0, names.len())].to_string();
+            let values = &evidence_trait.values;
+            evidence.push((
+                evidence_trait.name.to_string(),
+                values[rand.range(0, values.len())].to_string(),
+            ));
+        }
 
-        let age = rand.range(22, 65);
+        // An accomplice leaves some of the traces found on the victim, while
+        // the killer keeps whatever was left on the weapon.
+        let mut accomplice_evidence = vec![];
+        if rand.range(0, 100) < config.accomplice_chance {
+            let mut on_victim: Vec<String> = evidence
+                .iter()
+                .filter(|(attribute, _)| {
+                    content.evidence_trait(attribute).found_on == TraceSource::Victim
+                })
+                .map(|(attribute, _)| attribute.to_string())
+                .collect();
+            let most = if on_victim.len() == evidence.len() {
+                on_victim.len() - 1
+            } else {
+                on_victim.len()
+            };
 
-        let weapons = ["knife", "gun", "wrench", "poison"];
-        let weapon_used = weapons[rand.range(0, weapons.len())].to_string();
+            if most > 0 {
+                for _ in 0..rand.range(1, most + 1) {
+                    accomplice_evidence.push(on_victim.remove(rand.range(0, on_victim.len())));
+                }
+            }
+        }
 
-        let hair_colors = ["black", "blonde", "red"];
-        let hair_found = hair_colors[rand.range(0, hair_colors.len())].to_string();
+        let relationships = &content.relationships;
+        let relationship = &relationships[rand.range(0, relationships.len())];
+        let relationship_found = relationship.name.to_string();
 
-        let shoe_prints = ["small", "average", "large"];
-        let shoe_print = shoe_prints[rand.range(0, shoe_prints.len())].to_string();
+        let time_of_death = rand.range(content.hours_of_death.0, content.hours_of_death.1);
+
+        let locations = &building.rooms;
+        let crime_scene = locations[rand.range(0, locations.len())].to_string();
 
         let color = rltk::RED;
 
-        let display = vec![
-            "      ___      ".to_string(),
-            "     /   \\     ".to_string(),
-            "     |   |     ".to_string(),
-            "     \\   /     ".to_string(),
-            "    __| |__    ".to_string(),
-            "   /       \\   ".to_string(),
-            "  / /|   |\\ \\  ".to_string(),
-            " / / |   | \\ \\ ".to_string(),
-            "/_/  |   |  \\_\\".to_string(),
-            "     |   |     ".to_string(),
-            "     / ^ \\     ".to_string(),
-            "    / / \\ \\    ".to_string(),
-            "   / /   \\ \\   ".to_string(),
-            "  /_/     \\_\\  ".to_string(),
-        ];
+        let display = content.victim_display.clone();
 
         let mut tags = vec![];
 
-        match weapon_used.as_str() {
-            "knife" => {
-                tags.push(Note::new(
-                    vec![
-                        ("The".to_string(), rltk::WHITE, false),
-                        ("victim".to_string(), color, false),
-                        ("died from a".to_string(), rltk::WHITE, false),
-                        ("stab wound".to_string(), color, true),
-                    ],
-                    Some(ConnectionType::MurderWeapon),
-                ));
-            }
-            "gun" => {
-                tags.push(Note::new(
-                    vec![
-                        ("The".to_string(), rltk::WHITE, false),
-                        ("victim".to_string(), color, false),
-                        ("died from a".to_string(), rltk::WHITE, false),
-                        ("gunshot wound".to_string(), color, true),
-                    ],
-                    Some(ConnectionType::MurderWeapon),
-                ));
-            }
-            "wrench" => {
-                tags.push(Note::new(
-                    vec![
-                        ("The".to_string(), rltk::WHITE, false),
-                        ("victim".to_string(), color, false),
-                        ("died from".to_string(), rltk::WHITE, false),
-                        ("blunt force trauma".to_string(), color, true),
-                    ],
-                    Some(ConnectionType::MurderWeapon),
-                ));
-            }
-            "poison" => {
-                tags.push(Note::new(
-                    vec![
-                        ("The".to_string(), rltk::WHITE, false),
-                        ("victim".to_string(), color, false),
-                        ("died from".to_string(), rltk::WHITE, false),
-                        ("poisoning".to_string(), color, true),
-                    ],
-                    Some(ConnectionType::MurderWeapon),
-                ));
+        let (died_from, wound) = &content.weapon(&weapon_used).cause_of_death;
+        tags.push(Note::new(
+            vec![
+                ("The".to_string(), rltk::WHITE, false),
+                ("victim".to_string(), color, false),
+                (died_from.to_string(), rltk::WHITE, false),
+                (wound.to_string(), color, true),
+            ],
+            Some(ConnectionType::MurderWeapon),
+        ));
+
+        for (attribute, value) in evidence.iter() {
+            let evidence_trait = content.evidence_trait(attribute);
+            if evidence_trait.found_on != TraceSource::Victim {
+                continue;
             }
-            _ => {}
+
+            tags.push(Note::new(
+                vec![
+                    ("Near the".to_string(), rltk::WHITE, false),
+                    ("victim".to_string(), color, false),
+                    ("I found".to_string(), rltk::WHITE, false),
+                    (evidence_trait.trace(value), color, true),
+                ],
+                Some(ConnectionType::Evidence(attribute.to_string())),
+            ));
         }
 
         tags.push(Note::new(
             vec![
-                ("The killer left".to_string(), rltk::WHITE, false),
-                (format!("{} footprints", shoe_print), color, true),
-                ("next to the".to_string(), rltk::WHITE, false),
+                ("On the".to_string(), rltk::WHITE, false),
                 ("victim".to_string(), color, false),
+                ("I found".to_string(), rltk::WHITE, false),
+                (relationship.evidence.to_string(), color, true),
             ],
-            Some(ConnectionType::EvidenceShoeSize),
+            Some(ConnectionType::Motive),
         ));
 
-        let mut markers = vec![];
-
-        for tag in tags.clone() {
-            let mut rand = rltk::RandomNumberGenerator::new();
+        tags.push(Note::new(
+            vec![
+                ("The".to_string(), rltk::WHITE, false),
+                ("victim's".to_string(), color, false),
+                ("watch stopped at".to_string(), rltk::WHITE, false),
+                (format_hour(time_of_death), color, true),
+                ("in".to_string(), rltk::WHITE, false),
+                (crime_scene.to_string(), color, false),
+            ],
+            Some(ConnectionType::TimeOfDeath),
+        ));
 
-            let w = display[0].len() as i32;
-            let h = display.len() as i32;
+        let markers = place_markers(rand, &display, tags);
 
-            loop {
-                let x: i32 = EXAM_PANEL_WIDTH / 2 - w / 2 + rand.range(0, w);
-                let y: i32 = EXAM_PANEL_HEIGHT / 2 - h / 2 + rand.range(0, h);
+        // The coroner's report only arrives some time into the case.
+        let mut report = vec![];
 
-                let mut available = true;
+        let earliest = time_of_death - rand.range(0, 2);
+        report.push(Note::new(
+            vec![
+                (
+                    "The coroner puts the time of death between".to_string(),
+                    rltk::WHITE,
+                    false,
+                ),
+                (format_hour(earliest), color, true),
+                ("and".to_string(), rltk::WHITE, false),
+                (format_hour(earliest + 2), color, false),
+            ],
+            Some(ConnectionType::TimeOfDeath),
+        ));
 
-                for (marker_x, marker_y, _, _) in markers.clone() {
-                    let marker_x: i32 = marker_x;
-                    let marker_y: i32 = marker_y;
-                    if (x - marker_x).abs() <= 2 && (y - marker_y).abs() <= 2 {
-                        available = false;
-                        break;
-                    }
-                }
+        report.push(Note::new(
+            vec![
+                ("The coroner found".to_string(), rltk::WHITE, false),
+                (content.weapon(&weapon_used).wound.to_string(), color, true),
+            ],
+            Some(ConnectionType::Wound),
+        ));
 
-                if available {
-                    markers.push((x, y, tag, false));
-                    break;
-                }
-            }
-        }
+        let killer_traces: Vec<&(String, String)> = evidence
+            .iter()
+            .filter(|(attribute, _)| !accomplice_evidence.contains(attribute))
+            .collect();
+        let (forensic_trace, value) = killer_traces[rand.range(0, killer_traces.len())];
+        report.push(Note::new(
+            vec![
+                (
+                    "The coroner says the victim fought back against someone who".to_string(),
+                    rltk::WHITE,
+                    false,
+                ),
+                (
+                    content.evidence_trait(forensic_trace).describe(value),
+                    color,
+                    true,
+                ),
+            ],
+            Some(ConnectionType::Forensics),
+        ));
+        let forensic_trace = forensic_trace.to_string();
 
         let clue = Clue {
             name: format!("Victim: {}", &name),
@@ -173,113 +301,183 @@ impl Victim {
             is_murder_weapon: false,
             display,
             markers,
+            provenance: None,
         };
 
         Victim {
             name,
             age,
             weapon_used,
-            hair_found,
-            shoe_print,
+            evidence,
+            accomplice_evidence,
+            relationship_found,
+            time_of_death,
+            crime_scene,
             clue,
+            report,
+            forensic_trace,
+        }
+    }
+
+    /// Indices into `evidence` left by the killer and, if there is one, by
+    /// the accomplice.
+    pub fn evidence_groups(&self) -> Vec<Vec<usize>> {
+        let (accomplice, killer): (Vec<usize>, Vec<usize>) = (0..self.evidence.len())
+            .partition(|i| self.accomplice_evidence.contains(&self.evidence[*i].0));
+
+        if accomplice.is_empty() {
+            vec![killer]
+        } else {
+            vec![killer, accomplice]
         }
     }
 }
 
-#[derive(Component, Debug, Clone, Default)]
+#[derive(Component, Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Suspect {
     pub name: String,
     pub age: i32,
     pub color: (u8, u8, u8),
     pub is_killer: bool,
-    pub hair_color: String,
-    pub shoe_size: String,
+    pub is_accomplice: bool,
+    pub traits: Vec<(String, String)>,
+    pub traces: Vec<String>,
+    pub relationship: String,
+    pub motive: String,
+    pub whereabouts: Vec<(i32, String)>,
+    pub alibi: String,
+    pub claims: Vec<(String, String)>,
     pub convo_options: Vec<(String, String, Option<Note>)>,
 }
 
+/// The part a suspect plays in the case, and how likely they are to lie about
+/// themselves.
+struct SuspectRole {
+    is_killer: bool,
+    is_accomplice: bool,
+    is_red_herring: bool,
+    lie_chance: i32,
+}
+
 impl Suspect {
-    fn gen_rand(victim: &Victim, is_killer: bool) -> Self {
-        let mut rand = RandomNumberGenerator::new();
+    fn gen_rand(
+        rand: &mut RandomNumberGenerator,
+        content: &Content,
+        names: &mut NameGenerator,
+        victim: &Victim,
+        relationship: &RelationshipDef,
+        role: SuspectRole,
+    ) -> Self {
+        let SuspectRole {
+            is_killer,
+            is_accomplice,
+            is_red_herring,
+            lie_chance,
+        } = role;
 
-        let names = ["Adam", "Barry", "Charles"];
-        let name = names[rand.range(0, names.len())].to_string();
+        let name = names.gen_name(rand, content);
 
-        let age = rand.range(22, 65);
+        let age = rand.range(content.ages.0, content.ages.1);
 
-        let hair_colors = ["black", "blonde", "red"];
-        let mut hair_color = hair_colors[rand.range(0, hair_colors.len())].to_string();
+        let mut traits = vec![];
+        for (attribute, _) in victim.evidence.iter() {
+            let values = &content.evidence_trait(attribute).values;
+            traits.push((
+                attribute.to_string(),
+                values[rand.range(0, values.len())].to_string(),
+            ));
+        }
 
-        let shoe_sizes = ["small", "average", "large"];
-        let mut shoe_size = shoe_sizes[rand.range(0, shoe_sizes.len())].to_string();
+        // Each culprit matches the traces they left and nobody else matches
+        // all of them. A red herring comes within one trait of each culprit.
+        let mut traces = vec![];
+        for (g, group) in victim.evidence_groups().iter().enumerate() {
+            let owner = if g == 0 { is_killer } else { is_accomplice };
 
-        if is_killer {
-            if victim.hair_found != "none" {
-                hair_color = victim.hair_found.to_string();
-            }
-            if victim.shoe_print != "none" {
-                shoe_size = victim.shoe_print.to_string();
-            }
-        } else {
-            if hair_color == victim.hair_found.to_string()
-                && shoe_size == victim.shoe_print.to_string()
-            {
-                let mut rng = rltk::RandomNumberGenerator::new();
-
-                match rng.range(0, 2) {
-                    0 => loop {
-                        hair_color = hair_colors[rand.range(0, hair_colors.len())].to_string();
-                        if hair_color != victim.hair_found.to_string() {
-                            break;
-                        }
-                    },
-                    _ => loop {
-                        shoe_size = shoe_sizes[rand.range(0, shoe_sizes.len())].to_string();
-                        if shoe_size != victim.shoe_print.to_string() {
-                            break;
-                        }
-                    },
+            if owner {
+                for i in group.iter() {
+                    traits[*i].1 = victim.evidence[*i].1.to_string();
+                    traces.push(victim.evidence[*i].0.to_string());
                 }
+            } else if is_red_herring {
+                let odd = group[rand.range(0, group.len())];
+                for i in group.iter() {
+                    let (attribute, found) = &victim.evidence[*i];
+                    traits[*i].1 = if *i == odd {
+                        lie_about(rand, &content.evidence_trait(attribute).values, found)
+                    } else {
+                        found.to_string()
+                    };
+                }
+            } else if group.iter().all(|i| traits[*i].1 == victim.evidence[*i].1) {
+                let i = group[rand.range(0, group.len())];
+                let (attribute, found) = &victim.evidence[i];
+                traits[i].1 = lie_about(rand, &content.evidence_trait(attribute).values, found);
             }
         }
 
         let color = if is_killer { rltk::RED } else { rltk::YELLOW };
 
+        let mut claims = vec![];
+
+        for (attribute, value) in traits.iter() {
+            let claimed = if rand.range(0, 100) < lie_chance {
+                lie_about(rand, &content.evidence_trait(attribute).values, value)
+            } else {
+                value.to_string()
+            };
+            claims.push((attribute.to_string(), claimed));
+        }
+
         let mut convo_options = vec![];
 
+        let dialogue = &content.dialogue;
+        let fields = [
+            ("name", name.as_str()),
+            ("victim", victim.name.as_str()),
+            ("relationship", relationship.name.as_str()),
+        ];
+
         convo_options.push((
-            "Hello. What is your name?".to_string(),
-            format!("Hello Detective. My name is {}", name.clone()),
+            dialogue.greeting.0.clone(),
+            fill_template(&dialogue.greeting.1, &fields),
             None,
         ));
 
-        convo_options.push((
-            "What is your shoe size?".to_string(),
-            format!("My shoe size is {}", shoe_size.clone()),
-            Some(Note::new(
-                vec![
-                    (format!("{}", name.clone()), color, false),
-                    ("has".to_string(), rltk::WHITE, false),
-                    (format!("{} shoe size", shoe_size), color, true),
-                ],
-                if is_killer {
-                    Some(ConnectionType::EvidenceShoeSize)
-                } else {
-                    None
-                },
-            )),
-        ));
+        for ((attribute, value), (_, claimed)) in traits.iter().zip(claims.iter()) {
+            let evidence_trait = content.evidence_trait(attribute);
+
+            convo_options.push((
+                evidence_trait.question.0.clone(),
+                fill_template(&evidence_trait.question.1, &[("value", claimed)]),
+                Some(
+                    Note::new(
+                        vec![
+                            (name.to_string(), color, false),
+                            (evidence_trait.describe(claimed), color, true),
+                        ],
+                        if traces.contains(attribute) && claimed == value {
+                            Some(ConnectionType::Evidence(attribute.to_string()))
+                        } else {
+                            None
+                        },
+                    )
+                    .with_fact(&name, attribute, claimed),
+                ),
+            ));
+        }
 
         convo_options.push((
-            "What is your hair color?".to_string(),
-            format!("My hair color is {}", hair_color.clone()),
+            dialogue.relationship.0.clone(),
+            fill_template(&dialogue.relationship.1, &fields),
             Some(Note::new(
                 vec![
-                    (format!("{}", name.clone()), color, false),
-                    ("has".to_string(), rltk::WHITE, false),
-                    (format!("{} hair", hair_color), color, true),
+                    (name.clone(), color, false),
+                    ("was the victim's".to_string(), rltk::WHITE, false),
+                    (relationship.name.to_string(), color, true),
                 ],
                 if is_killer {
-                    Some(ConnectionType::EvidenceHair)
+                    Some(ConnectionType::Motive)
                 } else {
                     None
                 },
@@ -291,41 +489,932 @@ impl Suspect {
             age,
             color,
             is_killer,
-            hair_color,
-            shoe_size,
+            is_accomplice,
+            traits,
+            traces,
+            relationship: relationship.name.to_string(),
+            motive: relationship.motive.to_string(),
+            whereabouts: vec![],
+            alibi: String::new(),
+            claims,
             convo_options,
         }
     }
 
-    fn gen_rand_suspects(victim: &Victim) -> Vec<Suspect> {
+    fn gen_rand_suspects(
+        rand: &mut RandomNumberGenerator,
+        content: &Content,
+        config: &CaseConfig,
+        names: &mut NameGenerator,
+        victim: &Victim,
+        building: &BuildingDef,
+    ) -> Vec<Suspect> {
         let mut suspects = vec![];
 
-        for i in 0..3 {
-            suspects.push(Suspect::gen_rand(victim, i == 0));
+        let mut relationships: Vec<&RelationshipDef> = content
+            .relationships
+            .iter()
+            .filter(|r| r.name != victim.relationship_found)
+            .collect();
+
+        let killer = rand.range(0, config.suspect_count);
+        let accomplice = if victim.accomplice_evidence.is_empty() {
+            None
+        } else {
+            Some((killer + 1 + rand.range(0, config.suspect_count - 1)) % config.suspect_count)
+        };
+        let mut red_herrings = config.red_herrings;
+
+        for i in 0..config.suspect_count {
+            let is_killer = i == killer;
+            let is_accomplice = Some(i) == accomplice;
+            let is_red_herring = !is_killer && !is_accomplice && red_herrings > 0;
+            if is_red_herring {
+                red_herrings -= 1;
+            }
+
+            let relationship = if is_killer {
+                content.relationship(&victim.relationship_found)
+            } else {
+                relationships.remove(rand.range(0, relationships.len()))
+            };
+
+            suspects.push(Suspect::gen_rand(
+                rand,
+                content,
+                names,
+                victim,
+                relationship,
+                SuspectRole {
+                    is_killer,
+                    is_accomplice,
+                    is_red_herring,
+                    lie_chance: if is_killer || is_accomplice {
+                        config.killer_lie_chance
+                    } else {
+                        config.innocent_lie_chance
+                    },
+                },
+            ));
         }
 
+        Suspect::gen_whereabouts(rand, content, victim, building, &mut suspects);
+        Suspect::gen_gossip(rand, content, &mut suspects);
+
         suspects
     }
+
+    fn gen_whereabouts(
+        rand: &mut RandomNumberGenerator,
+        content: &Content,
+        victim: &Victim,
+        building: &BuildingDef,
+        suspects: &mut [Suspect],
+    ) {
+        let locations = &building.rooms;
+        let elsewhere: Vec<&String> = locations
+            .iter()
+            .filter(|location| **location != victim.crime_scene)
+            .collect();
+
+        for suspect in suspects.iter_mut() {
+            for hour in victim.time_of_death - 1..=victim.time_of_death + 1 {
+                // The accomplice was at the scene to prepare, but not for
+                // the murder itself.
+                let location = if hour == victim.time_of_death - 1 && suspect.is_accomplice {
+                    victim.crime_scene.to_string()
+                } else if hour == victim.time_of_death {
+                    if suspect.is_killer {
+                        victim.crime_scene.to_string()
+                    } else {
+                        elsewhere[rand.range(0, elsewhere.len())].to_string()
+                    }
+                } else {
+                    locations[rand.range(0, locations.len())].to_string()
+                };
+
+                suspect.whereabouts.push((hour, location));
+            }
+
+            suspect.alibi = if suspect.is_killer {
+                elsewhere[rand.range(0, elsewhere.len())].to_string()
+            } else {
+                suspect.location_at(victim.time_of_death).to_string()
+            };
+            suspect
+                .claims
+                .push((WHEREABOUTS.to_string(), suspect.alibi.to_string()));
+        }
+
+        let killer = suspects.iter().find(|s| s.is_killer).unwrap().clone();
+        let innocents: Vec<usize> = (0..suspects.len())
+            .filter(|i| !suspects[*i].is_killer && !suspects[*i].is_accomplice)
+            .collect();
+        let witness = innocents[rand.range(0, innocents.len())];
+        let time = format_hour(victim.time_of_death);
+        let dialogue = &content.dialogue;
+
+        for i in 0..suspects.len() {
+            let companion = suspects
+                .iter()
+                .enumerate()
+                .find(|(j, other)| *j != i && !other.is_killer && other.alibi == suspects[i].alibi)
+                .map(|(_, other)| other.name.to_string());
+
+            let suspect = &mut suspects[i];
+            let fields = [
+                ("time", time.as_str()),
+                ("location", suspect.alibi.as_str()),
+            ];
+
+            suspect.convo_options.push((
+                fill_template(&dialogue.alibi.0, &fields),
+                fill_template(&dialogue.alibi.1, &fields),
+                Some(
+                    Note::new(
+                        vec![
+                            (suspect.name.to_string(), suspect.color, false),
+                            ("says they were in".to_string(), rltk::WHITE, false),
+                            (suspect.alibi.to_string(), suspect.color, true),
+                            (format!("at {}", time), rltk::WHITE, false),
+                        ],
+                        if suspect.is_killer {
+                            Some(ConnectionType::Alibi)
+                        } else {
+                            None
+                        },
+                    )
+                    .with_fact(&suspect.name, WHEREABOUTS, &suspect.alibi),
+                ),
+            ));
+
+            let question = fill_template(&dialogue.witness.question, &fields);
+
+            if i == witness {
+                let fields = [
+                    ("suspect", killer.name.as_str()),
+                    ("location", victim.crime_scene.as_str()),
+                ];
+                suspect.convo_options.push((
+                    question,
+                    fill_template(&dialogue.witness.saw, &fields),
+                    Some(
+                        Note::new(
+                            vec![
+                                (suspect.name.to_string(), suspect.color, false),
+                                ("saw".to_string(), rltk::WHITE, false),
+                                (killer.name.to_string(), killer.color, false),
+                                ("go into".to_string(), rltk::WHITE, false),
+                                (victim.crime_scene.to_string(), killer.color, true),
+                                (format!("at {}", time), rltk::WHITE, false),
+                            ],
+                            Some(ConnectionType::Alibi),
+                        )
+                        .with_fact(
+                            &killer.name,
+                            WHEREABOUTS,
+                            &victim.crime_scene,
+                        ),
+                    ),
+                ));
+            } else if let Some(companion) = companion {
+                let fields = [
+                    ("suspect", companion.as_str()),
+                    ("location", suspect.alibi.as_str()),
+                ];
+                suspect.convo_options.push((
+                    question,
+                    fill_template(&dialogue.witness.together, &fields),
+                    Some(Note::new(
+                        vec![
+                            (suspect.name.to_string(), suspect.color, false),
+                            ("was with".to_string(), rltk::WHITE, false),
+                            (companion.to_string(), suspect.color, false),
+                            ("in".to_string(), rltk::WHITE, false),
+                            (suspect.alibi.to_string(), suspect.color, true),
+                        ],
+                        None,
+                    )),
+                ));
+            } else {
+                suspect.convo_options.push((
+                    question,
+                    fill_template(&dialogue.witness.alone, &fields),
+                    None,
+                ));
+            }
+        }
+    }
+
+    fn gen_gossip(rand: &mut RandomNumberGenerator, content: &Content, suspects: &mut [Suspect]) {
+        let mut gossip: Vec<(usize, usize, String)> = vec![];
+
+        for liar in 0..suspects.len() {
+            let attributes: Vec<String> = suspects[liar]
+                .traits
+                .iter()
+                .map(|(attribute, _)| attribute.to_string())
+                .collect();
+
+            for attribute in attributes.iter() {
+                if suspects[liar].claim(attribute) == suspects[liar].truth(attribute) {
+                    continue;
+                }
+
+                let tellers: Vec<usize> = (0..suspects.len())
+                    .filter(|teller| {
+                        *teller != liar
+                            && !suspects[*teller].is_killer
+                            && !gossip.iter().any(|(t, l, _)| t == teller && *l == liar)
+                    })
+                    .collect();
+
+                if !tellers.is_empty() {
+                    let teller = tellers[rand.range(0, tellers.len())];
+                    gossip.push((teller, liar, attribute.to_string()));
+                }
+            }
+        }
+
+        for teller in 0..suspects.len() {
+            if gossip.iter().any(|(t, _, _)| *t == teller) {
+                continue;
+            }
+
+            // Nobody repeats gossip someone else already gave, so each trait
+            // has a single note pointing at it.
+            let mut topics = vec![];
+            for (subject, suspect) in suspects.iter().enumerate() {
+                for (attribute, _) in suspect.traits.iter() {
+                    if subject != teller
+                        && !gossip
+                            .iter()
+                            .any(|(_, s, a)| *s == subject && a == attribute)
+                    {
+                        topics.push((subject, attribute.to_string()));
+                    }
+                }
+            }
+
+            if !topics.is_empty() {
+                let (subject, attribute) = topics.remove(rand.range(0, topics.len()));
+                gossip.push((teller, subject, attribute));
+            }
+        }
+
+        let dialogue = &content.dialogue;
+
+        for (teller, subject, attribute) in gossip {
+            let subject = suspects[subject].clone();
+            let value = subject.truth(&attribute);
+            let fields = [
+                ("suspect", subject.name.as_str()),
+                ("attribute", attribute.as_str()),
+                ("value", value.as_str()),
+            ];
+
+            let cxn_type =
+                if subject.traces.contains(&attribute) && subject.claim(&attribute) != value {
+                    Some(ConnectionType::Evidence(attribute.to_string()))
+                } else {
+                    None
+                };
+
+            let teller = &mut suspects[teller];
+            teller.convo_options.push((
+                fill_template(&dialogue.gossip.0, &fields),
+                fill_template(&dialogue.gossip.1, &fields),
+                Some(
+                    Note::new(
+                        vec![
+                            (teller.name.to_string(), teller.color, false),
+                            ("says".to_string(), rltk::WHITE, false),
+                            (format!("{}'s", subject.name), subject.color, false),
+                            (format!("{} is", attribute), rltk::WHITE, false),
+                            (value.to_string(), subject.color, true),
+                        ],
+                        cxn_type,
+                    )
+                    .with_fact(&subject.name, &attribute, &value),
+                ),
+            ));
+        }
+    }
+
+    fn gen_testimony(content: &Content, suspects: &mut [Suspect], clues: &[Clue]) {
+        let lines = &content.dialogue.provenance;
+
+        for suspect in suspects.iter_mut() {
+            let owned = clues.iter().find(|clue| match &clue.provenance {
+                Some(provenance) => provenance.owner.as_ref() == Some(&suspect.name),
+                None => false,
+            });
+
+            let (clue, provenance) = match owned {
+                Some(clue) => (clue, clue.provenance.as_ref().unwrap()),
+                None => {
+                    suspect
+                        .convo_options
+                        .push((lines.question.clone(), lines.none.clone(), None));
+                    continue;
+                }
+            };
+
+            let mut note = vec![
+                (suspect.name.to_string(), suspect.color, false),
+                ("owns a".to_string(), rltk::WHITE, false),
+                (clue.name.to_string(), suspect.color, true),
+                (format!("from {}", provenance.shop), rltk::WHITE, false),
+            ];
+
+            let mut fields = vec![
+                ("weapon", clue.name.as_str()),
+                ("shop", provenance.shop.as_str()),
+            ];
+
+            let answer = match &provenance.borrower {
+                Some(borrower) => {
+                    note.push(("and lent it to".to_string(), rltk::WHITE, false));
+                    note.push((borrower.to_string(), suspect.color, false));
+                    fields.push(("suspect", borrower.as_str()));
+                    fill_template(&lines.lender, &fields)
+                }
+                None => fill_template(&lines.owner, &fields),
+            };
+
+            suspect.convo_options.push((
+                lines.question.clone(),
+                answer,
+                Some(Note::new(
+                    note,
+                    if clue.is_murder_weapon {
+                        Some(ConnectionType::Provenance)
+                    } else {
+                        None
+                    },
+                )),
+            ));
+        }
+    }
+
+    pub fn truth(&self, attribute: &str) -> String {
+        match self.traits.iter().find(|(a, _)| a == attribute) {
+            Some((_, value)) => value.to_string(),
+            // Whereabouts are centred on the time of death.
+            None => self.whereabouts[self.whereabouts.len() / 2].1.to_string(),
+        }
+    }
+
+    pub fn claim(&self, attribute: &str) -> String {
+        self.claims
+            .iter()
+            .find(|(a, _)| a == attribute)
+            .map(|(_, value)| value.to_string())
+            .unwrap_or_else(|| self.truth(attribute))
+    }
+
+    pub fn location_at(&self, hour: i32) -> &str {
+        self.whereabouts
+            .iter()
+            .find(|(h, _)| *h == hour)
+            .map(|(_, location)| location.as_str())
+            .unwrap_or("")
+    }
+
+    /// Whether anything this suspect says is needed to solve the case, either
+    /// as part of a deduction or to catch someone else in a lie.
+    pub fn knows_too_much(&self, suspects: &[Suspect]) -> bool {
+        self.convo_options
+            .iter()
+            .filter_map(|(_, _, note)| note.as_ref())
+            .any(|note| {
+                note.cxn_type.is_some()
+                    || note.fact.as_ref().is_some_and(|fact| {
+                        suspects.iter().any(|s| {
+                            s.name == fact.subject && s.claim(&fact.attribute) != fact.value
+                        })
+                    })
+            })
+    }
+}
+
+/// A bystander who saw the killer leave the scene. Witnesses can be talked to
+/// like suspects but are never accused, and may misremember what they saw.
+#[derive(Component, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Witness {
+    pub name: String,
+    pub role: String,
+    pub age: i32,
+    pub color: (u8, u8, u8),
+    pub mistaken: bool,
+    pub sighting: (String, String),
+    pub convo_options: Vec<(String, String, Option<Note>)>,
+}
+
+impl Witness {
+    fn gen_rand(
+        rand: &mut RandomNumberGenerator,
+        content: &Content,
+        config: &CaseConfig,
+        names: &mut NameGenerator,
+        victim: &Victim,
+        role: String,
+    ) -> Self {
+        let name = names.gen_name(rand, content);
+
+        let age = rand.range(content.ages.0, content.ages.1);
+
+        let seen = &victim.evidence_groups()[0];
+        let (attribute, found) = &victim.evidence[seen[rand.range(0, seen.len())]];
+        let evidence_trait = content.evidence_trait(attribute);
+
+        let mistaken = rand.range(0, 100) < config.witness_error_chance;
+        let value = if mistaken {
+            lie_about(rand, &evidence_trait.values, found)
+        } else {
+            found.to_string()
+        };
+
+        let color = rltk::CYAN;
+
+        let time = format_hour(victim.time_of_death);
+        let description = evidence_trait.describe(&value);
+        let bystander = &content.dialogue.bystander;
+        let fields = [
+            ("name", name.as_str()),
+            ("role", role.as_str()),
+            ("time", time.as_str()),
+            ("location", victim.crime_scene.as_str()),
+            ("description", description.as_str()),
+        ];
+
+        let convo_options = vec![
+            (
+                bystander.greeting.0.clone(),
+                fill_template(&bystander.greeting.1, &fields),
+                None,
+            ),
+            (
+                fill_template(&bystander.sighting.0, &fields),
+                fill_template(&bystander.sighting.1, &fields),
+                Some(Note::new(
+                    vec![
+                        (name.to_string(), color, false),
+                        ("saw someone who".to_string(), rltk::WHITE, false),
+                        (description.to_string(), color, true),
+                        ("leave".to_string(), rltk::WHITE, false),
+                        (victim.crime_scene.to_string(), color, false),
+                        (format!("at {}", time), rltk::WHITE, false),
+                    ],
+                    if mistaken {
+                        None
+                    } else {
+                        Some(ConnectionType::Sighting(name.to_string()))
+                    },
+                )),
+            ),
+        ];
+
+        Witness {
+            name,
+            role,
+            age,
+            color,
+            mistaken,
+            sighting: (attribute.to_string(), value),
+            convo_options,
+        }
+    }
+
+    fn gen_rand_witnesses(
+        rand: &mut RandomNumberGenerator,
+        content: &Content,
+        config: &CaseConfig,
+        names: &mut NameGenerator,
+        victim: &Victim,
+    ) -> Vec<Witness> {
+        let mut witnesses = vec![];
+
+        let mut roles = content.witness_roles.clone();
+
+        for _ in 0..config.witness_count {
+            let role = roles.remove(rand.range(0, roles.len()));
+            witnesses.push(Witness::gen_rand(
+                rand, content, config, names, victim, role,
+            ));
+        }
+
+        witnesses
+    }
+}
+
+/// A later murder in a serial case, which only happens if the detective takes
+/// too long. The killer leaves the same trace on every body.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Strike {
+    pub victim: String,
+    pub was_suspect: bool,
+    pub time_of_death: i32,
+    pub crime_scene: String,
+    pub clue: Clue,
+}
+
+impl Strike {
+    fn gen_rand(
+        rand: &mut RandomNumberGenerator,
+        content: &Content,
+        first: &Victim,
+        victim: String,
+        was_suspect: bool,
+        building: &BuildingDef,
+    ) -> Self {
+        let time_of_death = rand.range(content.hours_of_death.0, content.hours_of_death.1);
+
+        let locations = &building.rooms;
+        let crime_scene = locations[rand.range(0, locations.len())].to_string();
+
+        let color = rltk::RED;
+
+        let (attribute, value) = first
+            .evidence
+            .iter()
+            .find(|(attribute, _)| *attribute == first.forensic_trace)
+            .unwrap();
+
+        let tags = vec![
+            Note::new(
+                vec![
+                    ("Near".to_string(), rltk::WHITE, false),
+                    (victim.to_string(), color, false),
+                    ("I found".to_string(), rltk::WHITE, false),
+                    (content.evidence_trait(attribute).trace(value), color, true),
+                ],
+                Some(ConnectionType::Pattern(victim.to_string())),
+            ),
+            Note::new(
+                vec![
+                    (format!("{}'s", victim), color, false),
+                    ("watch stopped at".to_string(), rltk::WHITE, false),
+                    (format_hour(time_of_death), color, true),
+                    ("in".to_string(), rltk::WHITE, false),
+                    (crime_scene.to_string(), color, false),
+                ],
+                None,
+            ),
+        ];
+
+        let display = content.victim_display.clone();
+        let markers = place_markers(rand, &display, tags);
+
+        let clue = Clue {
+            name: format!("Victim: {}", &victim),
+            color: RGB::named(color),
+            is_murder_weapon: false,
+            display,
+            markers,
+            provenance: None,
+        };
+
+        Strike {
+            victim,
+            was_suspect,
+            time_of_death,
+            crime_scene,
+            clue,
+        }
+    }
+
+    fn gen_rand_strikes(
+        rand: &mut RandomNumberGenerator,
+        content: &Content,
+        config: &CaseConfig,
+        names: &mut NameGenerator,
+        victim: &Victim,
+        suspects: &[Suspect],
+        building: &BuildingDef,
+    ) -> Vec<Strike> {
+        let mut strikes = vec![];
+
+        if config.max_strikes == 0 || rand.range(0, 100) >= config.serial_chance {
+            return strikes;
+        }
+
+        // Only someone the case doesn't hinge on can be killed off, and there
+        // has to be someone left to suspect besides the culprits.
+        let mut targets: Vec<&Suspect> = suspects
+            .iter()
+            .filter(|s| !s.is_killer && !s.is_accomplice && !s.knows_too_much(suspects))
+            .collect();
+        let mut spare = suspects.len() - 2;
+
+        for _ in 0..rand.range(1, config.max_strikes + 1) {
+            let strike = if spare > 0 && !targets.is_empty() && rand.range(0, 2) == 0 {
+                spare -= 1;
+                let target = targets.remove(rand.range(0, targets.len()));
+                Strike::gen_rand(
+                    rand,
+                    content,
+                    victim,
+                    target.name.to_string(),
+                    true,
+                    building,
+                )
+            } else {
+                let name = names.gen_name(rand, content);
+                Strike::gen_rand(rand, content, victim, name, false, building)
+            };
+
+            strikes.push(strike);
+        }
+
+        strikes
+    }
+}
+
+/// The coroner's findings, handed to the player once enough time has passed.
+pub struct CoronerReport {
+    pub victim: String,
+    pub due: i32,
+    pub notes: Vec<Note>,
+    pub delivered: bool,
 }
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+impl CoronerReport {
+    pub fn new(victim: &Victim, due: i32) -> Self {
+        CoronerReport {
+            victim: victim.name.to_string(),
+            due,
+            notes: victim.report.clone(),
+            delivered: false,
+        }
+    }
+}
+
+/// Counts down to the killer's next strike in a serial case. Each one gives
+/// the detective `delay` more minutes before the one after it.
+pub struct SerialKiller {
+    pub strikes: Vec<Strike>,
+    pub news: Vec<String>,
+    pub struck: usize,
+    pub due: i32,
+    pub delay: i32,
+}
+
+impl SerialKiller {
+    pub fn new(strikes: &[Strike], news: Vec<String>, now: i32, delay: i32) -> Self {
+        SerialKiller {
+            strikes: strikes.to_vec(),
+            news,
+            struck: 0,
+            due: now + delay,
+            delay,
+        }
+    }
+
+    /// Suspects who have been killed off and can no longer be accused.
+    pub fn dead_suspects(&self) -> Vec<String> {
+        self.strikes[..self.struck]
+            .iter()
+            .filter(|strike| strike.was_suspect)
+            .map(|strike| strike.victim.to_string())
+            .collect()
+    }
+}
+
+pub struct NameGenerator {
+    first_names: HashSet<String>,
+    last_names: HashSet<String>,
+}
+
+impl NameGenerator {
+    pub fn new() -> Self {
+        NameGenerator {
+            first_names: HashSet::new(),
+            last_names: HashSet::new(),
+        }
+    }
+
+    pub fn gen_name(&mut self, rand: &mut RandomNumberGenerator, content: &Content) -> String {
+        let first = NameGenerator::pick(rand, &content.first_names, &mut self.first_names);
+        let last = NameGenerator::pick(rand, &content.last_names, &mut self.last_names);
+
+        format!("{} {}", first, last)
+    }
+
+    fn pick(
+        rand: &mut RandomNumberGenerator,
+        pool: &[String],
+        used: &mut HashSet<String>,
+    ) -> String {
+        let unused: Vec<&String> = pool.iter().filter(|name| !used.contains(*name)).collect();
+
+        if unused.is_empty() {
+            panic!("Ran out of unique names in data/content.ron");
+        }
+
+        let name = unused[rand.range(0, unused.len())].to_string();
+        used.insert(name.to_string());
+        name
+    }
+}
+
+/// A room that stays locked until the detective gets hold of its key, which
+/// is either on the body or handed over by one of the suspects. The murder
+/// weapon is kept inside.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Lock {
+    pub room: String,
+    pub key: u32,
+}
+
+impl Lock {
+    fn gen_rand(
+        rand: &mut RandomNumberGenerator,
+        content: &Content,
+        config: &CaseConfig,
+        building: &BuildingDef,
+        victim: &mut Victim,
+        suspects: &mut [Suspect],
+        strikes: &[Strike],
+    ) -> Option<Lock> {
+        if rand.range(0, 100) >= config.locked_room_chance {
+            return None;
+        }
+
+        // No body is in the locked room, and nobody is waiting in it. Stairs
+        // never lead into it, so it can't be the only room on its floor.
+        let rooms: Vec<&String> = building
+            .rooms
+            .iter()
+            .filter(|room| {
+                **room != victim.crime_scene
+                    && building.shares_floor(room)
+                    && !suspects.iter().any(|s| s.alibi == **room)
+                    && !strikes.iter().any(|strike| strike.crime_scene == **room)
+            })
+            .collect();
+        if rooms.is_empty() {
+            return None;
+        }
+        let room = rooms[rand.range(0, rooms.len())].to_string();
+
+        // Only someone with nothing to hide hands over a key, and only if
+        // they're still around to do it.
+        let holders: Vec<usize> = (0..suspects.len())
+            .filter(|i| {
+                let suspect = &suspects[*i];
+                !suspect.is_killer
+                    && !suspect.is_accomplice
+                    && !strikes.iter().any(|strike| strike.victim == suspect.name)
+            })
+            .collect();
+
+        let key = if holders.is_empty() || rand.range(0, 2) == 0 {
+            let note = Note::new(
+                vec![
+                    ("In the".to_string(), rltk::WHITE, false),
+                    ("victim's".to_string(), rltk::RED, false),
+                    ("pocket I found the key to".to_string(), rltk::WHITE, false),
+                    (room.to_string(), rltk::RED, true),
+                ],
+                None,
+            );
+            let key = note.id;
+            place_marker(rand, &victim.clue.display, &mut victim.clue.markers, note);
+            key
+        } else {
+            let holder = &mut suspects[holders[rand.range(0, holders.len())]];
+            let note = Note::new(
+                vec![
+                    (holder.name.to_string(), holder.color, false),
+                    ("gave me the key to".to_string(), rltk::WHITE, false),
+                    (room.to_string(), holder.color, true),
+                ],
+                None,
+            );
+            let key = note.id;
+            let fields = [("room", room.as_str())];
+            holder.convo_options.push((
+                fill_template(&content.dialogue.key.0, &fields),
+                fill_template(&content.dialogue.key.1, &fields),
+                Some(note),
+            ));
+            key
+        };
+
+        Some(Lock { room, key })
+    }
+}
+
+/// Scatters notes over a clue's display, keeping them far enough apart to be
+/// told from each other.
+fn place_markers(
+    rand: &mut RandomNumberGenerator,
+    display: &[String],
+    tags: Vec<Note>,
+) -> Vec<(i32, i32, Note, bool)> {
+    let mut markers = vec![];
+
+    for tag in tags {
+        place_marker(rand, display, &mut markers, tag);
+    }
+
+    markers
+}
+
+fn place_marker(
+    rand: &mut RandomNumberGenerator,
+    display: &[String],
+    markers: &mut Vec<(i32, i32, Note, bool)>,
+    tag: Note,
+) {
+    let w = display[0].len() as i32;
+    let h = display.len() as i32;
+
+    loop {
+        let x: i32 = EXAM_PANEL_WIDTH / 2 - w / 2 + rand.range(0, w);
+        let y: i32 = EXAM_PANEL_HEIGHT / 2 - h / 2 + rand.range(0, h);
+
+        let available = markers
+            .iter()
+            .all(|(marker_x, marker_y, _, _)| (x - marker_x).abs() > 2 || (y - marker_y).abs() > 2);
+
+        if available {
+            markers.push((x, y, tag, false));
+            return;
+        }
+    }
+}
+
+fn lie_about(rand: &mut RandomNumberGenerator, options: &[String], truth: &str) -> String {
+    let lies: Vec<&String> = options.iter().filter(|option| *option != truth).collect();
+    lies[rand.range(0, lies.len())].to_string()
+}
+
+pub fn format_hour(hour: i32) -> String {
+    format!("{:02}:00", hour % 24)
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
 pub enum ConnectionType {
     MurderWeapon,
-    EvidenceHair,
-    EvidenceShoeSize,
+    Wound,
+    Provenance,
+    Evidence(String),
+    Motive,
+    TimeOfDeath,
+    Alibi,
+    Sighting(String),
+    Forensics,
+    Pattern(String),
+    Culprit,
+}
+
+impl ConnectionType {
+    pub fn required(victim: &Victim, witnesses: &[Witness], clues: &[Clue]) -> Vec<ConnectionType> {
+        let mut required = vec![ConnectionType::MurderWeapon, ConnectionType::Wound];
+
+        // Cases from before weapons had owners have nothing to trace.
+        let traced = clues.iter().any(|clue| {
+            clue.is_murder_weapon
+                && clue
+                    .provenance
+                    .as_ref()
+                    .is_some_and(|provenance| provenance.owner.is_some())
+        });
+        if traced {
+            required.push(ConnectionType::Provenance);
+        }
+
+        for (attribute, _) in victim.evidence.iter() {
+            required.push(ConnectionType::Evidence(attribute.to_string()));
+        }
+
+        required.push(ConnectionType::Motive);
+        required.push(ConnectionType::TimeOfDeath);
+        required.push(ConnectionType::Alibi);
+
+        for witness in witnesses.iter().filter(|w| !w.mistaken) {
+            required.push(ConnectionType::Sighting(witness.name.to_string()));
+        }
+
+        required.push(ConnectionType::Forensics);
+        required.push(ConnectionType::Culprit);
+        required
+    }
 }
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
 pub struct Connection {
-    pub ids: (u32, u32),
+    pub ids: Vec<u32>,
     pub cxn_type: ConnectionType,
     pub note: Note,
 }
 
 impl Connection {
-    pub fn new(from: u32, to: u32, cxn_type: ConnectionType, note: Note) -> Self {
+    pub fn new(ids: Vec<u32>, cxn_type: ConnectionType, note: Note) -> Self {
         Connection {
-            ids: (from, to),
+            ids,
             cxn_type,
             note,
         }
@@ -334,19 +1423,19 @@ impl Connection {
     pub fn gen_connections(
         victim: &Victim,
         suspects: Vec<Suspect>,
+        witnesses: &[Witness],
         clues: Vec<Clue>,
+        strikes: &[Strike],
     ) -> Vec<Connection> {
-        let mut cxns = vec![];
-
-        for cxn_type in [
-            ConnectionType::MurderWeapon,
-            ConnectionType::EvidenceHair,
-            ConnectionType::EvidenceShoeSize,
-        ]
-        .iter()
-        {
+        let mut cxns: Vec<Connection> = vec![];
+
+        let killer = suspects.iter().find(|s| s.is_killer).unwrap().clone();
+        let accomplice = suspects.iter().find(|s| s.is_accomplice).cloned();
+
+        for cxn_type in ConnectionType::required(victim, witnesses, &clues).iter() {
             let mut ids = vec![];
             let mut note = Note::new(vec![], None);
+            let mut on_weapon = false;
 
             for marker in victim.clue.markers.clone() {
                 if marker.2.cxn_type.is_some() && marker.2.cxn_type.unwrap() == cxn_type.clone() {
@@ -354,11 +1443,18 @@ impl Connection {
                 }
             }
 
+            for r_note in victim.report.iter() {
+                if r_note.cxn_type == Some(cxn_type.clone()) {
+                    ids.push(r_note.id);
+                }
+            }
+
             for clue in clues.clone() {
                 for marker in clue.markers.clone() {
                     if marker.2.cxn_type.is_some() && marker.2.cxn_type.unwrap() == cxn_type.clone()
                     {
                         ids.push(marker.2.id);
+                        on_weapon = true;
 
                         if cxn_type.clone() == ConnectionType::MurderWeapon {
                             note = Note::new(
@@ -367,7 +1463,7 @@ impl Connection {
                                     ("The".to_string(), rltk::WHITE, false),
                                     ("murder weapon".to_string(), rltk::RED, false),
                                     ("must have been".to_string(), rltk::WHITE, false),
-                                    (clue.name.clone(), rltk::RED, false),
+                                    (clue.name.clone(), rltk::RED, true),
                                 ],
                                 None,
                             );
@@ -384,25 +1480,44 @@ impl Connection {
                     {
                         ids.push(s_note.clone().unwrap().id);
 
-                        if cxn_type.clone() == ConnectionType::EvidenceHair {
+                        let subject = match s_note.clone().unwrap().fact {
+                            Some(fact) => fact.subject,
+                            None => suspect.name.clone(),
+                        };
+
+                        if let ConnectionType::Evidence(attribute) = cxn_type {
+                            let culprit = if victim.accomplice_evidence.contains(attribute) {
+                                "as the accomplice"
+                            } else {
+                                "as the killer"
+                            };
                             note = Note::new(
                                 vec![
                                     ("Aha!".to_string(), rltk::GREEN, false),
-                                    (format!("{}", suspect.name.clone()), rltk::RED, false),
+                                    (subject.clone(), rltk::RED, false),
                                     ("has the same".to_string(), rltk::WHITE, false),
-                                    ("hair color".to_string(), rltk::RED, false),
-                                    ("as the killer".to_string(), rltk::WHITE, false),
+                                    (attribute.to_string(), rltk::RED, true),
+                                    (culprit.to_string(), rltk::WHITE, false),
+                                ],
+                                Some(ConnectionType::Culprit),
+                            );
+                        } else if cxn_type.clone() == ConnectionType::Motive {
+                            note = Note::new(
+                                vec![
+                                    ("Aha!".to_string(), rltk::GREEN, false),
+                                    (suspect.name.clone(), rltk::RED, false),
+                                    ("had a motive:".to_string(), rltk::WHITE, false),
+                                    (suspect.motive.clone(), rltk::RED, false),
                                 ],
                                 None,
                             );
-                        } else if cxn_type.clone() == ConnectionType::EvidenceShoeSize {
+                        } else if cxn_type.clone() == ConnectionType::Alibi && suspect.is_killer {
                             note = Note::new(
                                 vec![
                                     ("Aha!".to_string(), rltk::GREEN, false),
-                                    (format!("{}", suspect.name.clone()), rltk::RED, false),
-                                    ("has the same".to_string(), rltk::WHITE, false),
-                                    ("shoe size".to_string(), rltk::RED, false),
-                                    ("as the killer".to_string(), rltk::WHITE, false),
+                                    (suspect.name.clone(), rltk::RED, false),
+                                    ("lied about being in".to_string(), rltk::WHITE, false),
+                                    (suspect.alibi.clone(), rltk::RED, false),
                                 ],
                                 None,
                             );
@@ -411,26 +1526,255 @@ impl Connection {
                 }
             }
 
-            cxns.push(Connection::new(
-                ids[0],
-                ids[1],
-                cxn_type.clone(),
-                note.clone(),
+            // A sighting is matched against whatever gave away the killer's
+            // version of the same trait.
+            if cxn_type.clone() == ConnectionType::Wound {
+                for clue in clues.iter().filter(|clue| clue.is_murder_weapon) {
+                    for marker in clue.markers.iter() {
+                        if marker.2.cxn_type == Some(ConnectionType::MurderWeapon) {
+                            ids.push(marker.2.id);
+                        }
+                    }
+
+                    note = Note::new(
+                        vec![
+                            ("Aha!".to_string(), rltk::GREEN, false),
+                            (
+                                "The wound matches the blood on the".to_string(),
+                                rltk::WHITE,
+                                false,
+                            ),
+                            (clue.name.clone(), rltk::RED, true),
+                        ],
+                        None,
+                    );
+                }
+            }
+
+            if cxn_type.clone() == ConnectionType::Provenance {
+                for clue in clues.iter().filter(|clue| clue.is_murder_weapon) {
+                    let provenance = clue.provenance.as_ref().unwrap();
+                    let owner = provenance.owner.clone().unwrap_or_default();
+
+                    note = match &provenance.borrower {
+                        Some(borrower) => Note::new(
+                            vec![
+                                ("Aha!".to_string(), rltk::GREEN, false),
+                                (borrower.clone(), rltk::RED, true),
+                                ("had borrowed the".to_string(), rltk::WHITE, false),
+                                (clue.name.clone(), rltk::RED, false),
+                                (format!("from {}", owner), rltk::WHITE, false),
+                            ],
+                            None,
+                        ),
+                        None => Note::new(
+                            vec![
+                                ("Aha!".to_string(), rltk::GREEN, false),
+                                ("The".to_string(), rltk::WHITE, false),
+                                (clue.name.clone(), rltk::RED, false),
+                                ("belongs to".to_string(), rltk::WHITE, false),
+                                (owner, rltk::RED, true),
+                            ],
+                            None,
+                        ),
+                    };
+                }
+            }
+
+            if cxn_type.clone() == ConnectionType::TimeOfDeath {
+                note = Note::new(
+                    vec![
+                        ("Aha!".to_string(), rltk::GREEN, false),
+                        (victim.name.clone(), rltk::RED, false),
+                        ("died at".to_string(), rltk::WHITE, false),
+                        (format_hour(victim.time_of_death), rltk::RED, true),
+                        ("in".to_string(), rltk::WHITE, false),
+                        (victim.crime_scene.clone(), rltk::RED, false),
+                    ],
+                    Some(ConnectionType::Alibi),
+                );
+            }
+
+            if cxn_type.clone() == ConnectionType::Forensics {
+                ids.extend(Connection::evidence_note_ids(
+                    &suspects,
+                    &victim.forensic_trace,
+                ));
+
+                note = Note::new(
+                    vec![
+                        ("Aha!".to_string(), rltk::GREEN, false),
+                        (
+                            "The victim fought back against".to_string(),
+                            rltk::WHITE,
+                            false,
+                        ),
+                        (killer.name.clone(), rltk::RED, true),
+                    ],
+                    None,
+                );
+            }
+
+            if let ConnectionType::Sighting(name) = cxn_type {
+                let witness = witnesses.iter().find(|w| w.name == *name).unwrap();
+
+                for (_, _, w_note) in witness.convo_options.iter() {
+                    if let Some(w_note) = w_note {
+                        if w_note.cxn_type == Some(cxn_type.clone()) {
+                            ids.push(w_note.id);
+                        }
+                    }
+                }
+
+                ids.extend(Connection::evidence_note_ids(
+                    &suspects,
+                    &witness.sighting.0,
+                ));
+
+                note = Note::new(
+                    vec![
+                        ("Aha!".to_string(), rltk::GREEN, false),
+                        (witness.name.clone(), rltk::RED, false),
+                        ("must have seen".to_string(), rltk::WHITE, false),
+                        (killer.name.clone(), rltk::RED, true),
+                        ("leave the scene".to_string(), rltk::WHITE, false),
+                    ],
+                    None,
+                );
+            }
+
+            // Traces on a weapon only mean something once you know it was
+            // the murder weapon.
+            if on_weapon && cxn_type.clone() != ConnectionType::MurderWeapon {
+                if let Some(weapon) = cxns
+                    .iter()
+                    .find(|c| c.cxn_type == ConnectionType::MurderWeapon)
+                {
+                    ids.push(weapon.note.id);
+                }
+            }
+
+            // Deductions can build on earlier ones.
+            for cxn in cxns.iter() {
+                if cxn.note.cxn_type == Some(cxn_type.clone()) {
+                    ids.push(cxn.note.id);
+                }
+            }
+
+            if cxn_type.clone() == ConnectionType::Culprit {
+                note = match &accomplice {
+                    Some(accomplice) => Note::new(
+                        vec![
+                            ("Aha!".to_string(), rltk::GREEN, false),
+                            (
+                                "The traces point to two people:".to_string(),
+                                rltk::WHITE,
+                                false,
+                            ),
+                            (killer.name.clone(), rltk::RED, true),
+                            ("and".to_string(), rltk::WHITE, false),
+                            (accomplice.name.clone(), rltk::RED, false),
+                        ],
+                        None,
+                    ),
+                    None => Note::new(
+                        vec![
+                            ("Aha!".to_string(), rltk::GREEN, false),
+                            (
+                                "Every trace the killer left points to".to_string(),
+                                rltk::WHITE,
+                                false,
+                            ),
+                            (killer.name.clone(), rltk::RED, true),
+                        ],
+                        None,
+                    ),
+                };
+            }
+
+            if ids.len() >= 2 {
+                cxns.push(Connection::new(ids, cxn_type.clone(), note.clone()));
+            }
+        }
+
+        // The trace on a later body is the same one the coroner found on the
+        // first, so whoever matches it killed them both.
+        for strike in strikes.iter() {
+            let cxn_type = ConnectionType::Pattern(strike.victim.to_string());
+
+            let mut ids: Vec<u32> = strike
+                .clue
+                .markers
+                .iter()
+                .filter(|marker| marker.2.cxn_type == Some(cxn_type.clone()))
+                .map(|marker| marker.2.id)
+                .collect();
+            ids.extend(Connection::evidence_note_ids(
+                &suspects,
+                &victim.forensic_trace,
             ));
+
+            let note = Note::new(
+                vec![
+                    ("Aha!".to_string(), rltk::GREEN, false),
+                    (killer.name.clone(), rltk::RED, false),
+                    ("killed".to_string(), rltk::WHITE, false),
+                    (strike.victim.clone(), rltk::RED, true),
+                    ("as well".to_string(), rltk::WHITE, false),
+                ],
+                None,
+            );
+
+            if ids.len() >= 2 {
+                cxns.push(Connection::new(ids, cxn_type, note));
+            }
         }
 
-        rltk::console::log(&format!("{}", cxns.len()));
         cxns
     }
+
+    /// Whatever a suspect said that gave away the killer's version of a trait.
+    fn evidence_note_ids(suspects: &[Suspect], attribute: &str) -> Vec<u32> {
+        let matching = Some(ConnectionType::Evidence(attribute.to_string()));
+        let mut ids = vec![];
+
+        for suspect in suspects.iter() {
+            for (_, _, s_note) in suspect.convo_options.iter() {
+                if let Some(s_note) = s_note {
+                    if s_note.cxn_type == matching {
+                        ids.push(s_note.id);
+                    }
+                }
+            }
+        }
+
+        ids
+    }
 }
 
 static mut NOTE_ID: u32 = 0;
 
-#[derive(Component, Debug, PartialEq, Eq, Hash, Clone)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
+pub struct Fact {
+    pub subject: String,
+    pub attribute: String,
+    pub value: String,
+}
+
+impl Fact {
+    pub fn contradicts(&self, other: &Fact) -> bool {
+        self.subject == other.subject
+            && self.attribute == other.attribute
+            && self.value != other.value
+    }
+}
+
+#[derive(Component, Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
 pub struct Note {
     pub id: u32,
     pub note: Vec<(String, (u8, u8, u8), bool)>,
     pub cxn_type: Option<ConnectionType>,
+    pub fact: Option<Fact>,
 }
 
 impl Note {
@@ -440,18 +1784,38 @@ impl Note {
                 id: NOTE_ID,
                 note,
                 cxn_type,
+                fact: None,
             };
             NOTE_ID += 1;
             note
         }
     }
 
+    /// Keeps notes made from now on clear of ids already taken, such as by a
+    /// case loaded from a file.
+    pub fn reserve_ids(used: u32) {
+        unsafe {
+            if NOTE_ID <= used {
+                NOTE_ID = used + 1;
+            }
+        }
+    }
+
+    pub fn with_fact(mut self, subject: &str, attribute: &str, value: &str) -> Self {
+        self.fact = Some(Fact {
+            subject: subject.to_string(),
+            attribute: attribute.to_string(),
+            value: value.to_string(),
+        });
+        self
+    }
+
     pub fn get_log_msg(&self) -> String {
         let mut log_msg = String::new();
 
         for pair in self.note.clone() {
             log_msg.push_str(&pair.0);
-            log_msg.push_str(" ");
+            log_msg.push(' ');
         }
 
         log_msg
@@ -461,88 +1825,82 @@ impl Note {
 #[derive(Component, Debug, PartialEq, Clone)]
 pub struct PlayerNotes {
     pub notes: HashSet<Note>,
+    pub lies_caught: HashSet<(String, String)>,
 }
 
 impl PlayerNotes {
     pub fn new() -> Self {
         PlayerNotes {
             notes: HashSet::new(),
+            lies_caught: HashSet::new(),
         }
     }
 
     pub fn add_note(&mut self, note: Note) {
         self.notes.insert(note);
     }
+
+    pub fn catch_lie(&mut self, a: &Note, b: &Note) -> Option<Note> {
+        let (a, b) = match (&a.fact, &b.fact) {
+            (Some(a), Some(b)) => (a, b),
+            _ => return None,
+        };
+
+        if !a.contradicts(b)
+            || !self
+                .lies_caught
+                .insert((a.subject.to_string(), a.attribute.to_string()))
+        {
+            return None;
+        }
+
+        Some(Note::new(
+            vec![
+                ("Caught in a lie!".to_string(), rltk::GREEN, false),
+                (a.subject.to_string(), rltk::RED, false),
+                ("lied about their".to_string(), rltk::WHITE, false),
+                (a.attribute.to_string(), rltk::RED, false),
+            ],
+            None,
+        ))
+    }
 }
 
-#[derive(Component, Clone, Debug)]
+#[derive(Component, Clone, Debug, Serialize, Deserialize)]
 pub struct Clue {
     pub name: String,
     pub color: RGB,
     pub is_murder_weapon: bool,
     pub display: Vec<String>,
     pub markers: Vec<(i32, i32, Note, bool)>,
+    #[serde(default)]
+    pub provenance: Option<Provenance>,
 }
 
-impl Clue {
-    fn gen_rand(victim: &Victim, is_murder_weapon: bool) -> Self {
-        let mut rand = RandomNumberGenerator::new();
-
-        let names = ["knife", "gun", "wrench", "poison"];
-        let mut name = names[rand.range(0, names.len())].to_string();
-
-        if is_murder_weapon {
-            name = victim.weapon_used.to_string();
-        }
+/// Where a weapon came from, who it belongs to and who else had their hands
+/// on it.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Provenance {
+    pub shop: String,
+    pub owner: Option<String>,
+    pub borrower: Option<String>,
+}
 
-        let display = match name.as_str() {
-            "knife" => {
-                vec![
-                    "___________________________________ ______________________  ".to_string(),
-                    "\\                                  | (_)     (_)    (_)   \\ ".to_string(),
-                    " `.                                |  __________________   }".to_string(),
-                    "   `-..........................____|_(                  )_/ ".to_string(),
-                ]
-            }
-            "gun" => {
-                vec![
-                    " _ ________,".to_string(),
-                    " >`(==(----'".to_string(),
-                    "(__/~~`     ".to_string(),
-                ]
-            }
-            "poison" => {
-                vec![
-                    "     o=o     ".to_string(),
-                    "     | |     ".to_string(),
-                    "  ___| |___  ".to_string(),
-                    " /         \\ ".to_string(),
-                    "|    .-.    |".to_string(),
-                    "|   (0.0)   |".to_string(),
-                    "| '=.|m|.=' |".to_string(),
-                    "| .='`\"``=. |".to_string(),
-                    " \\_________/ ".to_string(),
-                ]
-            }
-            "wrench" => {
-                vec![
-                    "-------".to_string(),
-                    "|     |".to_string(),
-                    "|     |".to_string(),
-                    "|     |".to_string(),
-                    "|     |".to_string(),
-                    "|     |".to_string(),
-                    "-------".to_string(),
-                ]
-            }
-            _ => vec!["".to_string()],
-        };
+impl Clue {
+    fn gen_rand(
+        rand: &mut RandomNumberGenerator,
+        content: &Content,
+        victim: &Victim,
+        suspects: &[Suspect],
+        name: String,
+        owner: Option<&Suspect>,
+        borrower: Option<&Suspect>,
+    ) -> Self {
+        let is_murder_weapon = name == victim.weapon_used;
+        let weapon = content.weapon(&name);
+        let display = weapon.display.clone();
 
-        let color = if is_murder_weapon {
-            rltk::PURPLE
-        } else {
-            rltk::LIMEGREEN
-        };
+        let color = rltk::LIMEGREEN;
 
         let mut tags = vec![];
 
@@ -556,61 +1914,155 @@ impl Clue {
                 ],
                 Some(ConnectionType::MurderWeapon),
             ));
-            tags.push(Note::new(
-                vec![
-                    ("While examining".to_string(), rltk::WHITE, false),
-                    (format!("{},", name.clone()), color, false),
-                    ("I found".to_string(), rltk::WHITE, false),
-                    (format!("{} hair", victim.hair_found), color, true),
-                ],
-                Some(ConnectionType::EvidenceHair),
-            ));
+
+            for (attribute, value) in victim.evidence.iter() {
+                let evidence_trait = content.evidence_trait(attribute);
+                if evidence_trait.found_on != TraceSource::Weapon {
+                    continue;
+                }
+
+                tags.push(Note::new(
+                    vec![
+                        ("While examining".to_string(), rltk::WHITE, false),
+                        (format!("{},", name.clone()), color, false),
+                        ("I found".to_string(), rltk::WHITE, false),
+                        (evidence_trait.trace(value), color, true),
+                    ],
+                    Some(ConnectionType::Evidence(attribute.to_string())),
+                ));
+            }
         }
 
-        let mut markers = vec![];
+        // Every weapon came with a receipt, but it only matters for the one
+        // that was used.
+        let shop = weapon.shops[rand.range(0, weapon.shops.len())].to_string();
+        tags.push(Note::new(
+            vec![
+                ("While examining".to_string(), rltk::WHITE, false),
+                (format!("{},", name.clone()), color, false),
+                ("I found".to_string(), rltk::WHITE, false),
+                (format!("a receipt from {}", shop), color, true),
+            ],
+            if is_murder_weapon && owner.is_some() {
+                Some(ConnectionType::Provenance)
+            } else {
+                None
+            },
+        ));
 
-        for tag in tags.clone() {
-            let mut rand = rltk::RandomNumberGenerator::new();
+        // Red herrings: plausible traces that don't connect to anything, so
+        // the weapon can't be told apart by whether it has markers at all.
+        let mut herrings = vec![];
 
-            let w = display[0].len() as i32;
-            let h = display.len() as i32;
+        for trace in weapon.traces.iter() {
+            herrings.push(trace.to_string());
+        }
 
-            loop {
-                let x: i32 = EXAM_PANEL_WIDTH / 2 - w / 2 + rand.range(0, w);
-                let y: i32 = EXAM_PANEL_HEIGHT / 2 - h / 2 + rand.range(0, h);
+        for (attribute, value) in victim.evidence.iter() {
+            let evidence_trait = content.evidence_trait(attribute);
+            if evidence_trait.found_on == TraceSource::Weapon {
+                herrings.push(evidence_trait.trace(&lie_about(
+                    rand,
+                    &evidence_trait.values,
+                    value,
+                )));
+            }
+        }
 
-                let mut available = true;
+        for suspect in suspects.iter().filter(|s| !s.is_killer) {
+            herrings.push(format!("{}'s fingerprints", suspect.name));
+        }
 
-                for (marker_x, marker_y, _, _) in markers.clone() {
-                    let marker_x: i32 = marker_x;
-                    let marker_y: i32 = marker_y;
-                    if (x - marker_x).abs() <= 2 && (y - marker_y).abs() <= 2 {
-                        available = false;
-                        break;
-                    }
-                }
+        let herring_count = if is_murder_weapon {
+            rand.range(0, 2)
+        } else {
+            rand.range(2, 4)
+        };
 
-                if available {
-                    markers.push((x, y, tag, false));
-                    break;
-                }
-            }
+        for _ in 0..herring_count {
+            let herring = herrings.remove(rand.range(0, herrings.len()));
+            tags.push(Note::new(
+                vec![
+                    ("While examining".to_string(), rltk::WHITE, false),
+                    (format!("{},", name.clone()), color, false),
+                    ("I found".to_string(), rltk::WHITE, false),
+                    (herring, color, true),
+                ],
+                None,
+            ));
         }
 
+        let markers = place_markers(rand, &display, tags);
+
         Clue {
             name,
             color: RGB::named(color),
             is_murder_weapon,
             display,
             markers,
+            provenance: Some(Provenance {
+                shop,
+                owner: owner.map(|s| s.name.to_string()),
+                borrower: borrower.map(|s| s.name.to_string()),
+            }),
         }
     }
 
-    fn gen_rand_clues(victim: &Victim) -> Vec<Clue> {
+    fn gen_rand_clues(
+        rand: &mut RandomNumberGenerator,
+        content: &Content,
+        config: &CaseConfig,
+        victim: &Victim,
+        suspects: &[Suspect],
+    ) -> Vec<Clue> {
         let mut clues = vec![];
 
-        for i in 0..3 {
-            clues.push(Clue::gen_rand(victim, i == 0));
+        let murder_weapon = rand.range(0, config.clue_count);
+
+        // Everyone owns at most one of the weapons lying around. The one that
+        // was used belongs to the killer, or to someone who lent it to them.
+        let killer = suspects.iter().position(|s| s.is_killer).unwrap();
+        let lenders: Vec<usize> = (0..suspects.len())
+            .filter(|i| !suspects[*i].is_killer && !suspects[*i].is_accomplice)
+            .collect();
+        let lent = !lenders.is_empty() && rand.range(0, 100) < config.lent_weapon_chance;
+        let weapon_owner = if lent {
+            lenders[rand.range(0, lenders.len())]
+        } else {
+            killer
+        };
+        let mut owners: Vec<usize> = (0..suspects.len()).filter(|i| *i != weapon_owner).collect();
+
+        // No two weapons lying around are the same kind, so that whatever is
+        // said or found about one can't be mistaken for another.
+        let mut decoys: Vec<&WeaponDef> = content
+            .weapons
+            .iter()
+            .filter(|weapon| weapon.name != victim.weapon_used)
+            .collect();
+
+        for i in 0..config.clue_count {
+            let (owner, borrower) = if i == murder_weapon {
+                (
+                    Some(&suspects[weapon_owner]),
+                    if lent { Some(&suspects[killer]) } else { None },
+                )
+            } else if !owners.is_empty() {
+                let owner = owners.remove(rand.range(0, owners.len()));
+                (Some(&suspects[owner]), None)
+            } else {
+                (None, None)
+            };
+
+            let name = if i == murder_weapon {
+                victim.weapon_used.to_string()
+            } else {
+                decoys.remove(rand.range(0, decoys.len())).name.to_string()
+            };
+
+            clues.push(Clue::gen_rand(
+                rand, content, victim, suspects, name, owner, borrower,
+            ));
         }
 
         clues
@@ -622,3 +2074,40 @@ impl Clue {
         self.markers.insert(idx, marker);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fact(subject: &str, attribute: &str, value: &str) -> Note {
+        Note::new(vec![(value.to_string(), rltk::WHITE, false)], None)
+            .with_fact(subject, attribute, value)
+    }
+
+    #[test]
+    fn contradicting_notes_catch_a_lie_once() {
+        let mut notes = PlayerNotes::new();
+        let claim = fact("Ada", "hair color", "red");
+        let truth = fact("Ada", "hair color", "black");
+
+        assert!(notes.catch_lie(&claim, &truth).is_some());
+        assert!(notes.catch_lie(&truth, &claim).is_none());
+    }
+
+    #[test]
+    fn other_notes_catch_nothing() {
+        let mut notes = PlayerNotes::new();
+        let claim = fact("Ada", "hair color", "red");
+
+        assert!(notes
+            .catch_lie(&claim, &fact("Ada", "hair color", "red"))
+            .is_none());
+        assert!(notes
+            .catch_lie(&claim, &fact("Bea", "hair color", "black"))
+            .is_none());
+        assert!(notes
+            .catch_lie(&claim, &fact("Ada", "shoe size", "9"))
+            .is_none());
+        assert!(notes.catch_lie(&claim, &Note::new(vec![], None)).is_none());
+    }
+}
@@ -0,0 +1,349 @@
+use std::collections::HashSet;
+
+use crate::constants::*;
+use crate::story::{Clue, ConnectionType, Fact, Note, Story};
+
+pub fn verify(story: &Story) -> Result<(), String> {
+    let killers = story.suspects.iter().filter(|s| s.is_killer).count();
+    if killers != 1 {
+        return Err(format!("expected one killer, found {}", killers));
+    }
+
+    let accomplices = story.suspects.iter().filter(|s| s.is_accomplice).count();
+    let expected = story.victim.evidence_groups().len() - 1;
+    if accomplices != expected {
+        return Err(format!(
+            "expected {} accomplices, found {}",
+            expected, accomplices
+        ));
+    }
+
+    let names: HashSet<&String> = story.suspects.iter().map(|s| &s.name).collect();
+    if names.len() != story.suspects.len() {
+        return Err("two suspects share a name".to_string());
+    }
+
+    for suspect in story.suspects.iter() {
+        if suspect.whereabouts.is_empty() {
+            return Err(format!("{} has no whereabouts", suspect.name));
+        }
+    }
+
+    let notes = discoverable_notes(story)?;
+    let mut known: HashSet<u32> = notes.iter().map(|note| note.id).collect();
+
+    // Deductions can only be made once every note they need is known.
+    loop {
+        let mut progress = false;
+
+        for cxn in story.connections.iter() {
+            if !known.contains(&cxn.note.id) && cxn.ids.iter().all(|id| known.contains(id)) {
+                known.insert(cxn.note.id);
+                progress = true;
+            }
+        }
+
+        if !progress {
+            break;
+        }
+    }
+
+    for cxn_type in ConnectionType::required(&story.victim, &story.witnesses, &story.clues) {
+        let cxn = match story.connections.iter().find(|c| c.cxn_type == cxn_type) {
+            Some(cxn) => cxn,
+            None => return Err(format!("{:?} has no connection", cxn_type)),
+        };
+
+        if cxn.ids.len() < 2 {
+            return Err(format!("{:?} connects fewer than 2 notes", cxn_type));
+        }
+        if !known.contains(&cxn.note.id) {
+            return Err(format!("{:?} can never be deduced", cxn_type));
+        }
+
+        let stray = notes
+            .iter()
+            .chain(story.connections.iter().map(|c| &c.note))
+            .filter(|note| note.cxn_type == Some(cxn_type.clone()) && !cxn.ids.contains(&note.id))
+            .count();
+        if stray > 0 {
+            return Err(format!(
+                "{:?} has {} notes outside its connection",
+                cxn_type, stray
+            ));
+        }
+    }
+
+    if let Some(lock) = &story.lock {
+        if lock.room == story.victim.crime_scene
+            || story.suspects.iter().any(|s| s.alibi == lock.room)
+            || story.strikes.iter().any(|s| s.crime_scene == lock.room)
+        {
+            return Err(format!("{} can't be locked", lock.room));
+        }
+        if !notes.iter().any(|note| note.id == lock.key) {
+            return Err(format!("the key to {} can never be found", lock.room));
+        }
+    }
+
+    for strike in story.strikes.iter() {
+        if strike.was_suspect
+            && !story
+                .suspects
+                .iter()
+                .any(|s| s.name == strike.victim && !s.is_killer && !s.is_accomplice)
+        {
+            return Err(format!("{} can't be killed off", strike.victim));
+        }
+
+        let cxn_type = ConnectionType::Pattern(strike.victim.to_string());
+        match story.connections.iter().find(|c| c.cxn_type == cxn_type) {
+            Some(cxn) if cxn.ids.len() >= 2 && known.contains(&cxn.note.id) => {}
+            _ => return Err(format!("{:?} can never be deduced", cxn_type)),
+        }
+    }
+
+    let weapons: Vec<&Clue> = story
+        .clues
+        .iter()
+        .filter(|clue| clue.is_murder_weapon)
+        .collect();
+    if weapons.len() != 1 {
+        return Err(format!(
+            "expected one murder weapon, found {}",
+            weapons.len()
+        ));
+    }
+    if story
+        .clues
+        .iter()
+        .filter(|clue| clue.name == weapons[0].name)
+        .count()
+        > 1
+    {
+        return Err(format!("more than one {} on the map", weapons[0].name));
+    }
+
+    if let Some(provenance) = &weapons[0].provenance {
+        let killer = story.suspects.iter().find(|s| s.is_killer).unwrap();
+        let holder = provenance.borrower.as_ref().or(provenance.owner.as_ref());
+        if provenance.owner.is_some() && holder != Some(&killer.name) {
+            return Err(format!(
+                "the {} never passed through {}'s hands",
+                weapons[0].name, killer.name
+            ));
+        }
+    }
+
+    let evidence = &story.victim.evidence;
+    for (g, group) in story.victim.evidence_groups().iter().enumerate() {
+        for suspect in story.suspects.iter() {
+            let owner = if g == 0 {
+                suspect.is_killer
+            } else {
+                suspect.is_accomplice
+            };
+            let matches = group
+                .iter()
+                .all(|i| suspect.truth(&evidence[*i].0) == evidence[*i].1);
+
+            if owner && !matches {
+                return Err(format!("{} does not match their traces", suspect.name));
+            }
+            if !owner && matches {
+                return Err(format!("{} also matches the evidence", suspect.name));
+            }
+        }
+    }
+
+    for suspect in story.suspects.iter() {
+        let at_scene = suspect.location_at(story.victim.time_of_death) == story.victim.crime_scene;
+        if suspect.is_killer != at_scene {
+            return Err(format!("{} has the wrong whereabouts", suspect.name));
+        }
+
+        let has_motive = suspect.relationship == story.victim.relationship_found;
+        if suspect.is_killer != has_motive {
+            return Err(format!("{} has the wrong motive", suspect.name));
+        }
+    }
+
+    let killer = story.suspects.iter().find(|s| s.is_killer).unwrap();
+    for witness in story.witnesses.iter() {
+        let (attribute, value) = &witness.sighting;
+        if (killer.truth(attribute) == *value) == witness.mistaken {
+            return Err(format!("{}'s sighting is wrong", witness.name));
+        }
+    }
+
+    for suspect in story.suspects.iter() {
+        for (attribute, claim) in suspect.claims.iter() {
+            let truth = suspect.truth(attribute);
+            if *claim != truth
+                && !notes.iter().any(|note| {
+                    note.fact
+                        == Some(Fact {
+                            subject: suspect.name.to_string(),
+                            attribute: attribute.to_string(),
+                            value: truth.to_string(),
+                        })
+                })
+            {
+                return Err(format!(
+                    "{}'s lie about {} is never exposed",
+                    suspect.name, attribute
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn discoverable_notes(story: &Story) -> Result<Vec<Note>, String> {
+    let mut notes = vec![];
+
+    notes.extend(story.victim.report.iter().cloned());
+
+    let bodies = story.strikes.iter().map(|strike| &strike.clue);
+
+    for clue in story
+        .clues
+        .iter()
+        .chain(Some(&story.victim.clue))
+        .chain(bodies)
+    {
+        for (x, y, note, _) in clue.markers.iter() {
+            if !(1..EXAM_PANEL_WIDTH - 1).contains(x) || !(3..EXAM_PANEL_HEIGHT - 3).contains(y) {
+                return Err(format!("marker on {} is outside the panel", clue.name));
+            }
+            notes.push(note.clone());
+        }
+    }
+
+    // Anyone who may be killed off can't be counted on to talk.
+    let speakers = story
+        .suspects
+        .iter()
+        .filter(|s| !story.strikes.iter().any(|strike| strike.victim == s.name))
+        .map(|s| &s.convo_options)
+        .chain(story.witnesses.iter().map(|w| &w.convo_options));
+
+    for convo_options in speakers {
+        for (_, _, note) in convo_options.iter() {
+            if let Some(note) = note {
+                notes.push(note.clone());
+            }
+        }
+    }
+
+    Ok(notes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{CaseConfig, Difficulty};
+    use crate::content::Content;
+    use rltk::RandomNumberGenerator;
+
+    const DIFFICULTIES: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard];
+
+    fn story(seed: u64, difficulty: Difficulty) -> Story {
+        let mut rng = RandomNumberGenerator::seeded(seed);
+        Story::gen_rand(
+            seed,
+            &mut rng,
+            &Content::load(),
+            &CaseConfig::new(difficulty),
+        )
+    }
+
+    #[test]
+    fn generated_cases_are_solvable() {
+        for difficulty in DIFFICULTIES.iter() {
+            for seed in 0..10 {
+                assert_eq!(verify(&story(seed, *difficulty)), Ok(()), "case #{}", seed);
+            }
+        }
+    }
+
+    #[test]
+    fn two_killers_are_rejected() {
+        let mut story = story(1, Difficulty::Normal);
+        let innocent = story.suspects.iter().position(|s| !s.is_killer).unwrap();
+        story.suspects[innocent].is_killer = true;
+
+        assert_eq!(
+            verify(&story),
+            Err("expected one killer, found 2".to_string())
+        );
+    }
+
+    #[test]
+    fn unexposed_lies_are_rejected() {
+        // Find an innocent lie and silence everyone who could give it away.
+        let (mut story, truth) = (0..)
+            .map(|seed| story(seed, Difficulty::Hard))
+            .find_map(|story| {
+                let truth = story
+                    .suspects
+                    .iter()
+                    .filter(|s| !s.is_killer && !s.is_accomplice)
+                    .flat_map(|s| {
+                        s.claims
+                            .iter()
+                            .filter(move |(attribute, claim)| *claim != s.truth(attribute))
+                            .map(move |(attribute, _)| Fact {
+                                subject: s.name.to_string(),
+                                attribute: attribute.to_string(),
+                                value: s.truth(attribute),
+                            })
+                    })
+                    .next()?;
+                Some((story, truth))
+            })
+            .unwrap();
+        for suspect in story.suspects.iter_mut() {
+            suspect
+                .convo_options
+                .retain(|(_, _, note)| note.as_ref().and_then(|n| n.fact.as_ref()) != Some(&truth));
+        }
+
+        assert_eq!(
+            verify(&story),
+            Err(format!(
+                "{}'s lie about {} is never exposed",
+                truth.subject, truth.attribute
+            ))
+        );
+    }
+
+    #[test]
+    fn locks_keep_clear_of_the_scene_and_alibis() {
+        let stories: Vec<Story> = (0..20)
+            .map(|seed| story(seed, Difficulty::Hard))
+            .filter(|story| story.lock.is_some())
+            .collect();
+        assert!(!stories.is_empty());
+
+        for story in stories.iter() {
+            let room = &story.lock.as_ref().unwrap().room;
+            assert_ne!(*room, story.victim.crime_scene);
+            assert!(story.suspects.iter().all(|s| s.alibi != *room));
+            assert!(story.strikes.iter().all(|s| s.crime_scene != *room));
+        }
+    }
+
+    #[test]
+    fn locked_crime_scenes_are_rejected() {
+        let mut story = (0..)
+            .map(|seed| story(seed, Difficulty::Hard))
+            .find(|story| story.lock.is_some())
+            .unwrap();
+        let scene = story.victim.crime_scene.to_string();
+        story.lock.as_mut().unwrap().room = scene.to_string();
+
+        assert_eq!(verify(&story), Err(format!("{} can't be locked", scene)));
+    }
+}